@@ -0,0 +1,151 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::{err::BaselineErr, lint::LintResult};
+
+use lib::Report;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use vfs::ReadOnlyVfs;
+
+/// A finding recorded in a baseline file. Findings are identified by the
+/// lint code, the file and the source they point to, rather than their
+/// position, so that unrelated edits do not invalidate the baseline.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct Finding {
+    file: PathBuf,
+    code: u32,
+    fingerprint: String,
+}
+
+impl Finding {
+    fn new(report: &Report, file: &Path, src: &str) -> Self {
+        let file = normalize_path(file);
+        let snippet = report
+            .diagnostics
+            .iter()
+            .map(|d| normalize_snippet(&src[d.at]))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let mut hasher = Sha256::new();
+        hasher.update(report.code.to_le_bytes());
+        hasher.update(file.to_string_lossy().as_bytes());
+        hasher.update([0]);
+        hasher.update(snippet.as_bytes());
+        let fingerprint = hex::encode(hasher.finalize());
+
+        Self {
+            file,
+            code: report.code,
+            fingerprint,
+        }
+    }
+}
+
+/// Findings are recorded with paths relative to the directory of the
+/// baseline file, so that a baseline matches however files are named on
+/// the command line.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Baseline {
+    findings: Vec<Finding>,
+    #[serde(skip)]
+    root: PathBuf,
+}
+
+impl Baseline {
+    /// Record all reports in `results` as findings, for a baseline file
+    /// written to `path`
+    pub fn new<P: AsRef<Path>>(
+        results: &[LintResult],
+        vfs: &ReadOnlyVfs,
+        path: P,
+    ) -> Result<Self, BaselineErr> {
+        let root = root_of(path.as_ref())?;
+        let mut findings = results
+            .iter()
+            .flat_map(|result| {
+                let path = relative_path(&root, vfs.file_path(result.file_id));
+                let src = vfs.get_str(result.file_id);
+                result
+                    .reports
+                    .iter()
+                    .map(move |report| Finding::new(report, &path, src))
+            })
+            .collect::<Vec<_>>();
+        findings.sort();
+        Ok(Self { findings, root })
+    }
+
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, BaselineErr> {
+        let contents = fs::read_to_string(path.as_ref())?;
+        let baseline: Self = serde_json::from_str(&contents).map_err(BaselineErr::Parse)?;
+        Ok(Self {
+            root: root_of(path.as_ref())?,
+            ..baseline
+        })
+    }
+
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), BaselineErr> {
+        let contents = serde_json::to_string_pretty(self).map_err(BaselineErr::Parse)?;
+        fs::write(path, contents + "\n")?;
+        Ok(())
+    }
+
+    /// Remove reports that are recorded in the baseline from `results`.
+    /// Identical findings are matched by count, if the baseline records
+    /// two of them and a third appears, one report is kept.
+    pub fn filter(&self, results: &mut Vec<LintResult>, vfs: &ReadOnlyVfs) {
+        let mut remaining: HashMap<&Finding, usize> = HashMap::new();
+        for finding in &self.findings {
+            *remaining.entry(finding).or_default() += 1;
+        }
+
+        for result in results.iter_mut() {
+            let path = relative_path(&self.root, vfs.file_path(result.file_id));
+            let src = vfs.get_str(result.file_id);
+            result.reports.retain(|report| {
+                let finding = Finding::new(report, &path, src);
+                match remaining.get_mut(&finding) {
+                    Some(count) if *count > 0 => {
+                        *count -= 1;
+                        false
+                    }
+                    _ => true,
+                }
+            });
+        }
+        results.retain(|result| !result.reports.is_empty());
+    }
+}
+
+// the directory holding the baseline file at `path`
+fn root_of(path: &Path) -> Result<PathBuf, BaselineErr> {
+    let dir = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    Ok(fs::canonicalize(dir)?)
+}
+
+// files outside of `root` are recorded by their absolute path
+fn relative_path(root: &Path, file: &Path) -> PathBuf {
+    let file = fs::canonicalize(file).unwrap_or_else(|_| normalize_path(file));
+    file.strip_prefix(root)
+        .map_or_else(|_| file.clone(), Path::to_path_buf)
+}
+
+// `./a.nix` and `a.nix` refer to the same file
+fn normalize_path(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .collect()
+}
+
+// whitespace changes do not affect the fingerprint
+fn normalize_snippet(snippet: &str) -> String {
+    snippet.split_whitespace().collect::<Vec<_>>().join(" ")
+}
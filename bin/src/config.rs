@@ -1,16 +1,29 @@
 use std::{
+    collections::{BTreeMap, HashMap, HashSet},
     default::Default,
     fmt, fs,
+    io::{self, Read as _},
     path::{Path, PathBuf},
     str::FromStr,
 };
 
-use crate::{LintMap, dirs, err::ConfigErr, utils};
+use crate::{
+    LintMap,
+    dirs::{self, Walker},
+    err::ConfigErr,
+    session,
+    utils::{self, ConfiguredLint},
+};
 
 use clap::Parser;
-use lib::LINTS;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use lib::{
+    LINTS, Lint, Severity,
+    options::{LintOptions, OptionValue},
+    session::{SessionInfo, Version},
+};
 use serde::{Deserialize, Serialize};
-use vfs::ReadOnlyVfs;
+use vfs::{FileId, ReadOnlyVfs};
 
 #[derive(Parser, Debug)]
 #[clap(version, author, about)]
@@ -33,40 +46,105 @@ pub enum SubCommand {
     Dump(Dump),
     /// List all available lints
     List(List),
+    /// Start a language server, communicating over stdio
+    #[cfg(feature = "lsp")]
+    Lsp(Lsp),
+    /// Inspect config files
+    Config(Config),
 }
 
 #[derive(Parser, Debug)]
+#[clap(after_help = "EXIT CODES:
+    0    nothing was denied
+    1    errors were found
+    2    statix itself failed, as on an invalid config file
+    3    no errors, but warnings or hints were denied")]
 pub struct Check {
-    /// File or directory to run check on
-    #[clap(default_value = ".", parse(from_os_str))]
-    target: PathBuf,
+    /// Files or directories to run check on, defaults to the current directory
+    #[clap(parse(from_os_str))]
+    targets: Vec<PathBuf>,
+
+    /// Also check the files listed in this file, or stdin if `-`. Entries
+    /// are separated by newlines or NUL bytes, missing files are skipped
+    #[clap(long = "files-from", parse(from_os_str), conflicts_with = "streaming")]
+    files_from: Option<PathBuf>,
 
     /// Globs of file patterns to skip
     #[clap(short, long)]
     ignore: Vec<String>,
 
-    /// Don't respect .gitignore files
+    /// Don't respect .gitignore, .ignore and git exclude files
     #[clap(short, long)]
     unrestricted: bool,
 
     /// Output format.
-    #[cfg_attr(feature = "json", doc = "Supported values: stderr, errfmt, json")]
-    #[cfg_attr(not(feature = "json"), doc = "Supported values: stderr, errfmt")]
+    #[cfg_attr(
+        feature = "json",
+        doc = "Supported values: stderr, errfmt, json, sarif"
+    )]
+    #[cfg_attr(not(feature = "json"), doc = "Supported values: stderr, errfmt, sarif")]
     #[clap(short = 'o', long, default_value_t, parse(try_from_str))]
     pub format: OutFormat,
 
-    /// Path to statix.toml or its parent directory
-    #[clap(short = 'c', long = "config", default_value = ".")]
-    pub conf_path: PathBuf,
+    /// Path to statix.toml or its parent directory, by default the
+    /// statix.toml nearest to each file is used
+    #[clap(short = 'c', long = "config")]
+    pub conf_path: Option<PathBuf>,
 
     /// Enable "streaming" mode, accept file on stdin, output diagnostics on stdout
     #[clap(short, long = "stdin")]
     pub streaming: bool,
+
+    /// Watch for changes and lint changed files again
+    #[clap(short, long, conflicts_with = "streaming")]
+    pub watch: bool,
+
+    /// Lowest severity that causes a non-zero exit, 1 for errors and 3
+    /// for warnings or hints.
+    /// Supported values: error, warn, hint
+    #[clap(long, default_value_t, parse(try_from_str))]
+    pub deny: Severity,
+
+    /// Number of warnings tolerated before exiting with a non-zero code
+    #[clap(long = "max-warnings", default_value_t)]
+    pub max_warnings: usize,
+
+    /// Report only findings that are not recorded in this baseline file
+    #[clap(long, parse(from_os_str))]
+    pub baseline: Option<PathBuf>,
+
+    /// Record current findings to this baseline file, instead of reporting them
+    #[clap(
+        long = "write-baseline",
+        parse(from_os_str),
+        conflicts_with = "baseline"
+    )]
+    pub write_baseline: Option<PathBuf>,
+
+    /// Report only findings on lines changed since this git revision, or
+    /// in the unified diff read from stdin if `-`
+    #[clap(long = "diff-base", conflicts_with = "streaming")]
+    pub diff_base: Option<String>,
 }
 
 impl Check {
-    pub fn vfs(&self, extra_ignores: &[String]) -> Result<ReadOnlyVfs, ConfigErr> {
+    /// Walkers over each target, empty in streaming mode
+    pub fn walkers(&self, configs: &mut Configs) -> Result<Vec<Walker>, ConfigErr> {
         if self.streaming {
+            return Ok(Vec::new());
+        }
+        walkers(
+            &self.targets,
+            self.files_from.as_deref(),
+            self.diff_base.as_deref(),
+            &self.ignore,
+            self.unrestricted,
+            configs,
+        )
+    }
+
+    pub fn vfs(&self, walkers: &[Walker], configs: &mut Configs) -> Result<ReadOnlyVfs, ConfigErr> {
+        let vfs = if self.streaming {
             use std::io::{self, BufRead};
             let src = io::stdin()
                 .lock()
@@ -74,27 +152,37 @@ impl Check {
                 .map(|l| l.unwrap())
                 .collect::<Vec<String>>()
                 .join("\n");
-            Ok(ReadOnlyVfs::singleton("<stdin>", src.as_bytes()))
+            ReadOnlyVfs::singleton("<stdin>", src.as_bytes())
         } else {
-            let all_ignores = [self.ignore.as_slice(), extra_ignores].concat();
-            let ignore = dirs::build_ignore_set(&all_ignores, &self.target, self.unrestricted)?;
-            let files = dirs::walk_nix_files(ignore, &self.target)?;
-            Ok(vfs(&files.collect::<Vec<_>>()))
-        }
+            let mut files = Vec::new();
+            for walker in walkers {
+                files.extend(walker.files()?);
+            }
+            configs.remove_ignored(&mut files)?;
+            vfs(&files)
+        };
+        configs.assign_all(&vfs, self.streaming)?;
+        Ok(vfs)
     }
 }
 
 #[derive(Parser, Debug)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct Fix {
-    /// File or directory to run fix on
-    #[clap(default_value = ".", parse(from_os_str))]
-    target: PathBuf,
+    /// Files or directories to run fix on, defaults to the current directory
+    #[clap(parse(from_os_str))]
+    targets: Vec<PathBuf>,
+
+    /// Also fix the files listed in this file, or stdin if `-`. Entries
+    /// are separated by newlines or NUL bytes, missing files are skipped
+    #[clap(long = "files-from", parse(from_os_str), conflicts_with = "streaming")]
+    files_from: Option<PathBuf>,
 
     /// Globs of file patterns to skip
     #[clap(short, long)]
     ignore: Vec<String>,
 
-    /// Don't respect .gitignore files
+    /// Don't respect .gitignore, .ignore and git exclude files
     #[clap(short, long)]
     unrestricted: bool,
 
@@ -102,24 +190,85 @@ pub struct Fix {
     #[clap(short, long = "dry-run")]
     pub diff_only: bool,
 
-    /// Path to statix.toml or its parent directory
-    #[clap(short = 'c', long = "config", default_value = ".")]
-    pub conf_path: PathBuf,
+    /// Path to statix.toml or its parent directory, by default the
+    /// statix.toml nearest to each file is used
+    #[clap(short = 'c', long = "config")]
+    pub conf_path: Option<PathBuf>,
 
     /// Enable "streaming" mode, accept file on stdin, output diagnostics on stdout
     #[clap(short, long = "stdin")]
     pub streaming: bool,
+
+    /// Watch for changes and fix changed files again
+    #[clap(short, long, conflicts_with = "streaming")]
+    pub watch: bool,
+
+    /// Apply only fixes on lines changed since this git revision, or in
+    /// the unified diff read from stdin if `-`
+    #[clap(long = "diff-base", conflicts_with = "streaming")]
+    pub diff_base: Option<String>,
+
+    /// Also apply fixes that may change the behavior of the code, such as
+    /// eta reductions
+    #[clap(long = "unsafe-fixes")]
+    pub unsafe_fixes: bool,
+
+    /// Review each fix, and choose whether to apply it
+    #[clap(long, conflicts_with_all = &["streaming", "watch", "diff-only"])]
+    pub interactive: bool,
+
+    /// Do not fix files in place, print the edits of each file instead.
+    /// Supported values: json
+    #[clap(
+        short = 'o',
+        long,
+        parse(try_from_str),
+        conflicts_with_all = &["diff-only", "interactive"]
+    )]
+    pub format: Option<FixFormat>,
 }
 
 pub enum FixOut {
     Diff,
     Stream,
     Write,
+    Json,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub enum FixFormat {
+    Json,
+}
+
+impl FromStr for FixFormat {
+    type Err = &'static str;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            _ => Err("unknown fix format, try: json"),
+        }
+    }
 }
 
 impl Fix {
-    pub fn vfs(&self, extra_ignores: &[String]) -> Result<ReadOnlyVfs, ConfigErr> {
+    /// Walkers over each target, empty in streaming mode
+    pub fn walkers(&self, configs: &mut Configs) -> Result<Vec<Walker>, ConfigErr> {
         if self.streaming {
+            return Ok(Vec::new());
+        }
+        walkers(
+            &self.targets,
+            self.files_from.as_deref(),
+            self.diff_base.as_deref(),
+            &self.ignore,
+            self.unrestricted,
+            configs,
+        )
+    }
+
+    pub fn vfs(&self, walkers: &[Walker], configs: &mut Configs) -> Result<ReadOnlyVfs, ConfigErr> {
+        let vfs = if self.streaming {
             use std::io::{self, BufRead};
             let src = io::stdin()
                 .lock()
@@ -127,13 +276,17 @@ impl Fix {
                 .map(|l| l.unwrap())
                 .collect::<Vec<String>>()
                 .join("\n");
-            Ok(ReadOnlyVfs::singleton("<stdin>", src.as_bytes()))
+            ReadOnlyVfs::singleton("<stdin>", src.as_bytes())
         } else {
-            let all_ignores = [self.ignore.as_slice(), extra_ignores].concat();
-            let ignore = dirs::build_ignore_set(&all_ignores, &self.target, self.unrestricted)?;
-            let files = dirs::walk_nix_files(ignore, &self.target)?;
-            Ok(vfs(&files.collect::<Vec<_>>()))
-        }
+            let mut files = Vec::new();
+            for walker in walkers {
+                files.extend(walker.files()?);
+            }
+            configs.remove_ignored(&mut files)?;
+            vfs(&files)
+        };
+        configs.assign_all(&vfs, self.streaming)?;
+        Ok(vfs)
     }
 
     // i need this ugly helper because clap's data model
@@ -142,6 +295,8 @@ impl Fix {
     pub fn out(&self) -> FixOut {
         if self.diff_only {
             FixOut::Diff
+        } else if let Some(FixFormat::Json) = self.format {
+            FixOut::Json
         } else if self.streaming {
             FixOut::Stream
         } else {
@@ -168,9 +323,10 @@ pub struct Single {
     #[clap(short, long = "stdin")]
     pub streaming: bool,
 
-    /// Path to statix.toml or its parent directory
-    #[clap(short = 'c', long = "config", default_value = ".")]
-    pub conf_path: PathBuf,
+    /// Path to statix.toml or its parent directory, by default the
+    /// statix.toml nearest to the file is used
+    #[clap(short = 'c', long = "config")]
+    pub conf_path: Option<PathBuf>,
 }
 
 impl Single {
@@ -212,9 +368,42 @@ pub struct Explain {
 #[derive(Parser, Debug)]
 pub struct Dump {}
 
+#[derive(Parser, Debug)]
+pub struct Config {
+    #[clap(subcommand)]
+    pub cmd: ConfigCmd,
+}
+
+#[derive(Parser, Debug)]
+pub enum ConfigCmd {
+    /// Validate the config files that apply to a path, and print the
+    /// merged config along with where each setting was set
+    Check(ConfigCheck),
+}
+
+#[derive(Parser, Debug)]
+pub struct ConfigCheck {
+    /// File or directory to print the config of, defaults to the current directory
+    #[clap(parse(from_os_str), default_value = ".")]
+    pub target: PathBuf,
+
+    /// Path to statix.toml or its parent directory
+    #[clap(short = 'c', long = "config")]
+    pub conf_path: Option<PathBuf>,
+}
+
 #[derive(Parser, Debug)]
 pub struct List {}
 
+#[cfg(feature = "lsp")]
+#[derive(Parser, Debug)]
+pub struct Lsp {
+    /// Path to statix.toml or its parent directory, by default the
+    /// statix.toml nearest to each document is used
+    #[clap(short = 'c', long = "config")]
+    pub conf_path: Option<PathBuf>,
+}
+
 #[derive(Debug, Copy, Clone, Default)]
 pub enum OutFormat {
     #[cfg(feature = "json")]
@@ -222,6 +411,7 @@ pub enum OutFormat {
     Errfmt,
     #[default]
     StdErr,
+    Sarif,
 }
 
 impl fmt::Display for OutFormat {
@@ -234,6 +424,7 @@ impl fmt::Display for OutFormat {
                 Self::Json => "json",
                 Self::Errfmt => "errfmt",
                 Self::StdErr => "stderr",
+                Self::Sarif => "sarif",
             }
         )
     }
@@ -250,46 +441,207 @@ impl FromStr for OutFormat {
             "json" => Err("statix was not compiled with the `json` feature flag"),
             "errfmt" => Ok(Self::Errfmt),
             "stderr" => Ok(Self::StdErr),
-            _ => Err("unknown output format, try: json, errfmt"),
+            "sarif" => Ok(Self::Sarif),
+            _ => Err("unknown output format, try: json, errfmt, sarif"),
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct ConfFile {
+    /// Config files in parent directories are not read if set
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    root: bool,
+
     #[serde(default = "Vec::new")]
     disabled: Vec<String>,
 
+    /// Lints disabled by a parent config file that are enabled again
+    #[serde(default = "Vec::new", skip_serializing_if = "Vec::is_empty")]
+    enabled: Vec<String>,
+
     #[serde(default = "Vec::new")]
     pub ignore: Vec<String>,
+
+    /// The Nix version to lint for, taken from `STATIX_NIX_VERSION` or
+    /// detected with `nix --version` if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    nix_version: Option<String>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "BTreeMap::is_empty",
+        with = "severity_map"
+    )]
+    severity: BTreeMap<String, Severity>,
+
+    /// Options of individual lints, as in `[lints.repeated_keys]`
+    #[serde(default, rename = "lints", skip_serializing_if = "BTreeMap::is_empty")]
+    lint_options: BTreeMap<String, BTreeMap<String, toml::Value>>,
+
+    #[serde(default = "Vec::new", skip_serializing_if = "Vec::is_empty")]
+    overrides: Vec<Override>,
+}
+
+/// Settings for the files matching `files`, applied on top of the config
+/// file the override is in, as in `[[overrides]]`
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Override {
+    /// Patterns in the format of `.gitignore`, relative to the directory
+    /// of the config file
+    files: Vec<String>,
+
+    #[serde(default = "Vec::new", skip_serializing_if = "Vec::is_empty")]
+    disabled: Vec<String>,
+
+    #[serde(default = "Vec::new", skip_serializing_if = "Vec::is_empty")]
+    enabled: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    nix_version: Option<String>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "BTreeMap::is_empty",
+        with = "severity_map"
+    )]
+    severity: BTreeMap<String, Severity>,
+
+    #[serde(default, rename = "lints", skip_serializing_if = "BTreeMap::is_empty")]
+    lint_options: BTreeMap<String, BTreeMap<String, toml::Value>>,
+}
+
+// settings that a nested config file or an override sets on top of those
+// it extends
+struct Layer<'a> {
+    disabled: &'a [String],
+    enabled: &'a [String],
+    ignore: &'a [String],
+    nix_version: Option<&'a String>,
+    severity: &'a BTreeMap<String, Severity>,
+    lint_options: &'a BTreeMap<String, BTreeMap<String, toml::Value>>,
+}
+
+impl Override {
+    fn layer(&self) -> Layer<'_> {
+        Layer {
+            disabled: &self.disabled,
+            enabled: &self.enabled,
+            ignore: &[],
+            nix_version: self.nix_version.as_ref(),
+            severity: &self.severity,
+            lint_options: &self.lint_options,
+        }
+    }
+
+    // `files` as a matcher rooted at `dir`
+    fn matcher(&self, dir: &Path) -> Result<Gitignore, ConfigErr> {
+        let mut builder = GitignoreBuilder::new(dir);
+        for pattern in &self.files {
+            builder.add_line(None, pattern)?;
+        }
+        Ok(builder.build()?)
+    }
+}
+
+fn to_toml(value: OptionValue) -> toml::Value {
+    match value {
+        OptionValue::Bool(b) => toml::Value::Boolean(b),
+        OptionValue::Int(i) => toml::Value::Integer(i64::try_from(i).unwrap_or(i64::MAX)),
+    }
+}
+
+// `Severity` is serialized by name, as in `manual_inherit = "error"`
+mod severity_map {
+    use std::collections::BTreeMap;
+
+    use lib::Severity;
+    use serde::{Deserialize, Deserializer, Serializer, de::Error as _};
+
+    pub fn serialize<S: Serializer>(
+        map: &BTreeMap<String, Severity>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(
+            map.iter()
+                .map(|(name, severity)| (name, severity.to_string())),
+        )
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeMap<String, Severity>, D::Error> {
+        BTreeMap::<String, String>::deserialize(deserializer)?
+            .into_iter()
+            .map(|(name, severity)| {
+                severity
+                    .parse()
+                    .map(|severity| (name, severity))
+                    .map_err(D::Error::custom)
+            })
+            .collect()
+    }
 }
 
 impl ConfFile {
+    /// Read and validate the config file at `path`. Unknown fields, lint
+    /// names and options are rejected.
     pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, ConfigErr> {
         let path = path.as_ref();
+        let invalid = |message| ConfigErr::InvalidConfFile {
+            path: path.to_path_buf(),
+            message,
+        };
         let config_file = fs::read_to_string(path).map_err(ConfigErr::InvalidPath)?;
-        toml::de::from_str(&config_file).map_err(ConfigErr::ConfFileParse)
+        let conf_file: Self =
+            toml::de::from_str(&config_file).map_err(|err| {
+                match unknown_field(&err.to_string(), &config_file) {
+                    Some(message) => invalid(message),
+                    None => ConfigErr::ConfFileParse(err),
+                }
+            })?;
+        check_layer(&conf_file.layer()).map_err(invalid)?;
+        for (i, o) in conf_file.overrides.iter().enumerate() {
+            check_layer(&o.layer())
+                .map_err(|message| invalid(format!("[[overrides]] #{}: {message}", i + 1)))?;
+        }
+        Ok(conf_file)
     }
-    pub fn discover<P: AsRef<Path>>(path: P) -> Result<Self, ConfigErr> {
-        let cannonical_path = fs::canonicalize(path.as_ref()).map_err(ConfigErr::InvalidPath)?;
-        for p in cannonical_path.ancestors() {
-            let statix_toml_path = if p.is_dir() {
-                p.join("statix.toml")
-            } else {
-                p.to_path_buf()
-            };
-            if statix_toml_path.exists() {
-                return Self::from_path(statix_toml_path);
-            }
+    fn layer(&self) -> Layer<'_> {
+        Layer {
+            disabled: &self.disabled,
+            enabled: &self.enabled,
+            ignore: &self.ignore,
+            nix_version: self.nix_version.as_ref(),
+            severity: &self.severity,
+            lint_options: &self.lint_options,
         }
-        Ok(Self::default())
     }
     #[must_use]
     pub fn dump(&self) -> String {
         let ideal_config = {
             let disabled = vec![];
             let ignore = vec![".direnv".into()];
-            Self { disabled, ignore }
+            let lint_options = LINTS
+                .iter()
+                .filter(|l| !l.options().is_empty())
+                .map(|l| {
+                    let options = l
+                        .options()
+                        .iter()
+                        .map(|o| (o.name.to_owned(), to_toml(o.default)))
+                        .collect();
+                    (l.name().to_owned(), options)
+                })
+                .collect();
+            Self {
+                disabled,
+                ignore,
+                lint_options,
+                ..Self::default()
+            }
         };
         toml::ser::to_string_pretty(&ideal_config).unwrap()
     }
@@ -299,11 +651,486 @@ impl ConfFile {
             (*LINTS)
                 .iter()
                 .filter(|l| !self.disabled.iter().any(|check| check == l.name()))
-                .copied()
+                .map(|l| {
+                    ConfiguredLint::new(l.as_ref())
+                        .with_severity(self.severity.get(l.name()).copied())
+                })
                 .collect::<Vec<_>>()
                 .as_slice(),
         )
     }
+    /// The session files under this config are linted in. The version set
+    /// in the config file wins over `STATIX_NIX_VERSION`, which wins over
+    /// the version of the `nix` on `PATH`. Without any, files are linted
+    /// for the latest Nix.
+    pub fn session(&self) -> Result<SessionInfo, ConfigErr> {
+        let nix_version = match &self.nix_version {
+            Some(v) => Some(
+                v.parse()
+                    .map_err(|_| ConfigErr::ConfFileVersionParse(v.clone()))?,
+            ),
+            None => session::default_nix_version()?.map(|(v, _)| v),
+        };
+        Ok(SessionInfo::from_version(nix_version).with_options(self.options()?))
+    }
+    /// Options set under `[lints.<name>]`, checked against the options
+    /// each lint declares
+    pub fn options(&self) -> Result<LintOptions, ConfigErr> {
+        parse_options(&self.lint_options).map_err(ConfigErr::LintOption)
+    }
+}
+
+fn parse_options(
+    lint_options: &BTreeMap<String, BTreeMap<String, toml::Value>>,
+) -> Result<LintOptions, String> {
+    let mut options = LintOptions::new();
+    for (name, values) in lint_options {
+        let lint = find_lint(name)?;
+        for (key, value) in values {
+            let option = lint
+                .options()
+                .iter()
+                .find(|o| o.name == key)
+                .ok_or_else(|| {
+                    let hint = did_you_mean(key, lint.options().iter().map(|o| o.name));
+                    format!("`{name}` has no option `{key}`{hint}")
+                })?;
+            let value = match (option.default, value) {
+                (OptionValue::Bool(_), toml::Value::Boolean(b)) => OptionValue::Bool(*b),
+                (OptionValue::Int(_), toml::Value::Integer(i)) if *i >= 0 => {
+                    let i = i.unsigned_abs();
+                    if let Some(min) = option.min.filter(|min| i < *min) {
+                        return Err(format!(
+                            "`{name}.{key}` should be at least {min}, found `{i}`"
+                        ));
+                    }
+                    OptionValue::Int(i)
+                }
+                (default, _) => {
+                    return Err(format!(
+                        "`{name}.{key}` should be a {}, found `{value}`",
+                        default.type_name()
+                    ));
+                }
+            };
+            options
+                .entry(lint.name())
+                .or_default()
+                .insert(option.name, value);
+        }
+    }
+    Ok(options)
+}
+
+fn find_lint(name: &str) -> Result<&'static dyn Lint, String> {
+    LINTS
+        .iter()
+        .find(|l| l.name() == name)
+        .map(AsRef::as_ref)
+        .ok_or_else(|| {
+            let hint = did_you_mean(name, LINTS.iter().map(|l| l.name()));
+            format!("no lint named `{name}`{hint}")
+        })
+}
+
+// `, did you mean `candidate`?` if `name` looks like a typo of a candidate
+fn did_you_mean<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> String {
+    utils::closest(name, candidates)
+        .map(|candidate| format!(", did you mean `{candidate}`?"))
+        .unwrap_or_default()
+}
+
+// the keys of a config file and of its `[[overrides]]`, as written in
+// `statix.toml`, these are kept in sync with `ConfFile` and `Override`
+const CONF_FILE_FIELDS: &[&str] = &[
+    "root",
+    "disabled",
+    "enabled",
+    "ignore",
+    "nix_version",
+    "severity",
+    "lints",
+    "overrides",
+];
+const OVERRIDE_FIELDS: &[&str] = &[
+    "files",
+    "disabled",
+    "enabled",
+    "nix_version",
+    "severity",
+    "lints",
+];
+
+// serde rejects unknown fields with "unknown field `disable`, expected ...",
+// only the field is taken from the message. The table it is in is looked up
+// in `source` to suggest a field of that table. `None` if the message is
+// not about an unknown field, or the field cannot be found.
+fn unknown_field(message: &str, source: &str) -> Option<String> {
+    let field = message.strip_prefix("unknown field `")?.split('`').next()?;
+    let value = source.parse::<toml::Value>().ok()?;
+    let unknown = |table: &toml::Value, fields: &[&str]| {
+        table.get(field).is_some() && !fields.contains(&field)
+    };
+    let message = |fields: &[&str]| {
+        format!(
+            "unknown field `{field}`{}",
+            did_you_mean(field, fields.iter().copied())
+        )
+    };
+
+    if unknown(&value, CONF_FILE_FIELDS) {
+        return Some(message(CONF_FILE_FIELDS));
+    }
+    let overrides = value.get("overrides")?.as_array()?;
+    let i = overrides.iter().position(|o| unknown(o, OVERRIDE_FIELDS))?;
+    Some(format!(
+        "[[overrides]] #{}: {}",
+        i + 1,
+        message(OVERRIDE_FIELDS)
+    ))
+}
+
+// lint names, the nix version and lint options of a config file or override
+fn check_layer(layer: &Layer<'_>) -> Result<(), String> {
+    let names = layer
+        .disabled
+        .iter()
+        .chain(layer.enabled)
+        .chain(layer.severity.keys());
+    for name in names {
+        find_lint(name)?;
+    }
+    if let Some(nix_version) = layer.nix_version {
+        nix_version
+            .parse::<Version>()
+            .map_err(|_| format!("unable to parse nix version: `{nix_version}`"))?;
+    }
+    parse_options(layer.lint_options)?;
+    Ok(())
+}
+
+/// Where a setting of a merged config was set, a config file or one of
+/// its `[[overrides]]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Origin {
+    pub path: PathBuf,
+    pub override_index: Option<usize>,
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path.display())?;
+        if let Some(i) = self.override_index {
+            write!(f, " [[overrides]] #{}", i + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// Config files and overrides merged in order, each setting along with
+/// where it was set
+#[derive(Debug, Default)]
+pub struct Merged {
+    /// The config files and overrides that were merged, in order
+    pub origins: Vec<Origin>,
+    pub disabled: Vec<(String, Origin)>,
+    pub ignore: Vec<(String, Origin)>,
+    pub nix_version: Option<(String, Origin)>,
+    pub severity: BTreeMap<String, (Severity, Origin)>,
+    pub lint_options: BTreeMap<String, BTreeMap<String, (toml::Value, Origin)>>,
+}
+
+impl Merged {
+    // lists are extended, lints in `enabled` are removed from those that
+    // are disabled so far, other settings are overridden
+    fn extend(&mut self, layer: &Layer<'_>, origin: &Origin) {
+        self.origins.push(origin.clone());
+        self.disabled
+            .retain(|(name, _)| !layer.enabled.contains(name));
+        for name in layer.disabled {
+            if !self.disabled.iter().any(|(n, _)| n == name) {
+                self.disabled.push((name.clone(), origin.clone()));
+            }
+        }
+        self.ignore
+            .extend(layer.ignore.iter().map(|i| (i.clone(), origin.clone())));
+        if let Some(nix_version) = layer.nix_version {
+            self.nix_version = Some((nix_version.clone(), origin.clone()));
+        }
+        for (name, severity) in layer.severity {
+            self.severity
+                .insert(name.clone(), (*severity, origin.clone()));
+        }
+        for (name, options) in layer.lint_options {
+            let merged = self.lint_options.entry(name.clone()).or_default();
+            for (key, value) in options {
+                merged.insert(key.clone(), (value.clone(), origin.clone()));
+            }
+        }
+    }
+
+    /// The merged config, without origins
+    #[must_use]
+    pub fn conf_file(&self) -> ConfFile {
+        ConfFile {
+            disabled: self.disabled.iter().map(|(n, _)| n.clone()).collect(),
+            ignore: self.ignore.iter().map(|(i, _)| i.clone()).collect(),
+            nix_version: self.nix_version.as_ref().map(|(v, _)| v.clone()),
+            severity: self
+                .severity
+                .iter()
+                .map(|(name, (severity, _))| (name.clone(), *severity))
+                .collect(),
+            lint_options: self
+                .lint_options
+                .iter()
+                .map(|(name, options)| {
+                    let options = options
+                        .iter()
+                        .map(|(key, (value, _))| (key.clone(), value.clone()))
+                        .collect();
+                    (name.clone(), options)
+                })
+                .collect(),
+            ..ConfFile::default()
+        }
+    }
+}
+
+/// Config files that apply to the files being processed. Unless a config
+/// file is passed explicitly, each file uses the `statix.toml` files in its
+/// directory and the directories above it, up to one with `root = true`.
+/// Config files further down extend those above them, followed by the
+/// `[[overrides]]` matching the file. Config files are read once, and
+/// merged configs are shared between files.
+#[derive(Default)]
+pub struct Configs {
+    conf_path: Option<PathBuf>,
+    read: HashMap<PathBuf, ReadConfFile>,
+    chains: HashMap<PathBuf, Vec<PathBuf>>,
+    found: Vec<(Vec<Applied>, ConfFile, LintMap, SessionInfo)>,
+    files: HashMap<FileId, usize>,
+}
+
+// a config file along with matchers for its `ignore` and `[[overrides]]`,
+// rooted at its directory
+struct ReadConfFile {
+    conf_file: ConfFile,
+    ignore: Gitignore,
+    overrides: Vec<Gitignore>,
+}
+
+// a config file in the chain of a file, and the overrides of it that match
+// the file
+type Applied = (PathBuf, Vec<usize>);
+
+impl Configs {
+    #[must_use]
+    pub fn new(conf_path: Option<PathBuf>) -> Self {
+        Self {
+            conf_path,
+            ..Self::default()
+        }
+    }
+
+    /// Config file that applies to the file or directory at `path`
+    pub fn discover<P: AsRef<Path>>(&mut self, path: P) -> Result<&ConfFile, ConfigErr> {
+        let idx = self.index(path.as_ref())?;
+        Ok(&self.found[idx].1)
+    }
+
+    /// Lints files with the config file that applies to them
+    pub fn assign<P: AsRef<Path>>(&mut self, file_id: FileId, path: P) -> Result<(), ConfigErr> {
+        let idx = self.index(path.as_ref())?;
+        self.files.insert(file_id, idx);
+        Ok(())
+    }
+
+    // files in streaming mode use the config file of the working directory
+    fn assign_all(&mut self, vfs: &ReadOnlyVfs, streaming: bool) -> Result<(), ConfigErr> {
+        for entry in vfs.iter() {
+            let path = if streaming {
+                Path::new(".")
+            } else {
+                entry.file_path
+            };
+            self.assign(entry.file_id, path)?;
+        }
+        Ok(())
+    }
+
+    /// Lints to run on `file_id`, the file must have been assigned a
+    /// config file
+    #[must_use]
+    pub fn lints(&self, file_id: FileId) -> &LintMap {
+        &self.found[self.files[&file_id]].2
+    }
+
+    /// Session to lint `file_id` in, the file must have been assigned a
+    /// config file
+    #[must_use]
+    pub fn session(&self, file_id: FileId) -> &SessionInfo {
+        &self.found[self.files[&file_id]].3
+    }
+
+    // walkers apply the `ignore` lists of the config files of their
+    // targets, config files further down apply to the files under them
+    fn remove_ignored(&mut self, files: &mut Vec<PathBuf>) -> Result<(), ConfigErr> {
+        let mut kept = Vec::with_capacity(files.len());
+        for file in files.drain(..) {
+            if !self.ignored(&file)? {
+                kept.push(file);
+            }
+        }
+        *files = kept;
+        Ok(())
+    }
+
+    /// Whether `path` is ignored by the `ignore` list of a config file
+    /// that applies to it, patterns are relative to the directory of the
+    /// config file they are in
+    pub fn ignored<P: AsRef<Path>>(&mut self, path: P) -> Result<bool, ConfigErr> {
+        let path = fs::canonicalize(path)?;
+        let is_dir = path.is_dir();
+        Ok(self.chain(&path)?.iter().any(|conf_path| {
+            let dir = conf_path.parent().unwrap_or(conf_path);
+            path.starts_with(dir)
+                && self.read[conf_path]
+                    .ignore
+                    .matched_path_or_any_parents(&path, is_dir)
+                    .is_ignore()
+        }))
+    }
+
+    /// The config that applies to the file or directory at `path`, along
+    /// with where each setting was set
+    pub fn merged<P: AsRef<Path>>(&mut self, path: P) -> Result<Merged, ConfigErr> {
+        let applied = self.applied(path.as_ref())?;
+        Ok(self.merge(&applied))
+    }
+
+    fn index(&mut self, path: &Path) -> Result<usize, ConfigErr> {
+        let applied = self.applied(path)?;
+        if let Some(idx) = self.found.iter().position(|(a, ..)| *a == applied) {
+            return Ok(idx);
+        }
+
+        let conf_file = self.merge(&applied).conf_file();
+        let lints = conf_file.lints();
+        let session = conf_file.session()?;
+        self.found.push((applied, conf_file, lints, session));
+        Ok(self.found.len() - 1)
+    }
+
+    fn merge(&self, applied: &[Applied]) -> Merged {
+        let mut merged = Merged::default();
+        for (conf_path, overrides) in applied {
+            let read = &self.read[conf_path].conf_file;
+            let origin = Origin {
+                path: conf_path.clone(),
+                override_index: None,
+            };
+            merged.extend(&read.layer(), &origin);
+            for &i in overrides {
+                let origin = Origin {
+                    override_index: Some(i),
+                    ..origin.clone()
+                };
+                merged.extend(&read.overrides[i].layer(), &origin);
+            }
+        }
+        merged
+    }
+
+    // config files that apply to `path`, along with the overrides in each
+    // that match it
+    fn applied(&mut self, path: &Path) -> Result<Vec<Applied>, ConfigErr> {
+        let path = fs::canonicalize(path)?;
+        let chain = self.chain(&path)?;
+        let is_dir = path.is_dir();
+        let applied = chain
+            .into_iter()
+            .map(|conf_path| {
+                let dir = conf_path.parent().unwrap_or(&conf_path);
+                let overrides = if path.starts_with(dir) {
+                    self.read[&conf_path]
+                        .overrides
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, o)| o.matched_path_or_any_parents(&path, is_dir).is_ignore())
+                        .map(|(i, _)| i)
+                        .collect()
+                } else {
+                    Vec::new()
+                };
+                (conf_path, overrides)
+            })
+            .collect();
+        Ok(applied)
+    }
+
+    // config files that apply to `path`, outermost first
+    fn chain(&mut self, path: &Path) -> Result<Vec<PathBuf>, ConfigErr> {
+        let start = match &self.conf_path {
+            Some(conf_path) => fs::canonicalize(conf_path)?,
+            None => path.to_path_buf(),
+        };
+        // a config file passed explicitly is read on its own
+        if start.is_file() && self.conf_path.is_some() {
+            self.read(&start)?;
+            return Ok(vec![start]);
+        }
+        // the config is searched for starting from the directory of a
+        // file, not the file itself
+        let dir = if start.is_dir() {
+            start.as_path()
+        } else {
+            start.parent().unwrap_or(&start)
+        };
+        self.chain_of(dir)
+    }
+
+    // the chain of a directory extends that of its parent, chains are
+    // remembered so that each directory is looked into once
+    fn chain_of(&mut self, dir: &Path) -> Result<Vec<PathBuf>, ConfigErr> {
+        if let Some(chain) = self.chains.get(dir) {
+            return Ok(chain.clone());
+        }
+        let statix_toml_path = dir.join("statix.toml");
+        let found = statix_toml_path.is_file();
+        let root = found && self.read(&statix_toml_path)?.root;
+        let mut chain = match dir.parent() {
+            Some(parent) if !root => self.chain_of(parent)?,
+            _ => Vec::new(),
+        };
+        if found {
+            chain.push(statix_toml_path);
+        }
+        self.chains.insert(dir.to_path_buf(), chain.clone());
+        Ok(chain)
+    }
+
+    fn read(&mut self, conf_path: &Path) -> Result<&ConfFile, ConfigErr> {
+        if !self.read.contains_key(conf_path) {
+            let conf_file = ConfFile::from_path(conf_path)?;
+            let dir = conf_path.parent().unwrap_or(conf_path);
+            let ignore = dirs::build_ignore_set(&conf_file.ignore, dir, true)?;
+            let overrides = conf_file
+                .overrides
+                .iter()
+                .map(|o| o.matcher(dir))
+                .collect::<Result<_, _>>()?;
+            self.read.insert(
+                conf_path.to_path_buf(),
+                ReadConfFile {
+                    conf_file,
+                    ignore,
+                    overrides,
+                },
+            );
+        }
+        Ok(&self.read[conf_path].conf_file)
+    }
 }
 
 fn parse_line_col(src: &str) -> Result<(usize, usize), ConfigErr> {
@@ -337,9 +1164,66 @@ fn parse_warning_code(src: &str) -> Result<u32, ConfigErr> {
     }
 }
 
+// each target is walked with the ignore list of the config file that
+// applies to it
+fn walkers(
+    targets: &[PathBuf],
+    files_from: Option<&Path>,
+    diff_base: Option<&str>,
+    ignore: &[String],
+    unrestricted: bool,
+    configs: &mut Configs,
+) -> Result<Vec<Walker>, ConfigErr> {
+    // clap can only tell that both flags are present, not their values
+    if files_from == Some(Path::new("-")) && diff_base == Some("-") {
+        return Err(ConfigErr::StdinConflict);
+    }
+    let mut targets = targets.to_vec();
+    if let Some(files_from) = files_from {
+        targets.extend(read_files_from(files_from)?);
+    } else if targets.is_empty() {
+        targets.push(PathBuf::from("."));
+    }
+
+    targets
+        .into_iter()
+        .map(|target| {
+            let conf_file = configs.discover(&target)?;
+            let all_ignores = [ignore, conf_file.ignore.as_slice()].concat();
+            let ignore = dirs::build_ignore_set(&all_ignores, &target, unrestricted)?;
+            Ok(Walker::new(target, ignore, unrestricted))
+        })
+        .collect()
+}
+
+// `git diff --name-only` lists removed files as well, those are skipped
+fn read_files_from(path: &Path) -> Result<Vec<PathBuf>, ConfigErr> {
+    let list = if path == Path::new("-") {
+        let mut list = String::new();
+        io::stdin().read_to_string(&mut list)?;
+        list
+    } else {
+        fs::read_to_string(path)?
+    };
+    let separator = if list.contains('\0') { '\0' } else { '\n' };
+    Ok(list
+        .split(separator)
+        .map(|entry| entry.strip_suffix('\r').unwrap_or(entry))
+        .filter(|entry| !entry.is_empty())
+        .map(PathBuf::from)
+        .filter(|path| path.exists())
+        .collect())
+}
+
+// a file named by several targets, such as `.` and `./a.nix`, is read once,
+// under the first of its names
 fn vfs(files: &[PathBuf]) -> vfs::ReadOnlyVfs {
     let mut vfs = ReadOnlyVfs::default();
+    let mut seen = HashSet::new();
     for file in files {
+        if !seen.insert(fs::canonicalize(file).unwrap_or_else(|_| file.clone())) {
+            continue;
+        }
         if let Ok(data) = fs::read_to_string(file) {
             let _id = vfs.alloc_file_id(file);
             vfs.set_file_contents(file, data.as_bytes());
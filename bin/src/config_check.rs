@@ -0,0 +1,96 @@
+use std::fmt::Write as _;
+
+use crate::{config::Merged, session::VersionSource};
+
+use lib::session::Version;
+
+/// The merged config in the format of `statix.toml`, each setting is
+/// followed by a comment naming the config file or override it was set in.
+/// `default` is the version used when `nix_version` is not set, if any.
+#[must_use]
+pub fn render(merged: &Merged, default: Option<(Version, VersionSource)>) -> String {
+    let mut out = String::new();
+    if merged.origins.is_empty() {
+        out.push_str("# no config files apply, defaults are used\n");
+    } else {
+        out.push_str("# merged from, in order:\n");
+        for origin in &merged.origins {
+            writeln!(out, "#   {origin}").unwrap();
+        }
+    }
+
+    for (key, values) in [("disabled", &merged.disabled), ("ignore", &merged.ignore)] {
+        if values.is_empty() {
+            writeln!(out, "{key} = []").unwrap();
+            continue;
+        }
+        writeln!(out, "{key} = [").unwrap();
+        for (value, origin) in values {
+            write!(out, "    {}, # {origin}", quote(value)).unwrap();
+            // patterns are relative to the directory of their config file
+            if let ("ignore", Some(dir)) = (key, origin.path.parent()) {
+                write!(out, ", relative to {}", dir.display()).unwrap();
+            }
+            out.push('\n');
+        }
+        out.push_str("]\n");
+    }
+
+    match (&merged.nix_version, default) {
+        (Some((version, origin)), _) => {
+            writeln!(out, "nix_version = {} # {origin}", quote(version)).unwrap();
+        }
+        (None, Some((version, VersionSource::Env))) => {
+            writeln!(
+                out,
+                "# nix_version is not set, {version} is configured with STATIX_NIX_VERSION"
+            )
+            .unwrap();
+        }
+        (None, Some((version, VersionSource::Detected))) => {
+            writeln!(
+                out,
+                "# nix_version is not set, {version} is detected with `nix --version`"
+            )
+            .unwrap();
+        }
+        (None, None) => out.push_str("# nix_version is not set, the latest Nix is targeted\n"),
+    }
+
+    if !merged.severity.is_empty() {
+        out.push_str("\n[severity]\n");
+        for (name, (severity, origin)) in &merged.severity {
+            writeln!(out, "{name} = {} # {origin}", quote(&severity.to_string())).unwrap();
+        }
+    }
+
+    for (name, options) in &merged.lint_options {
+        writeln!(out, "\n[lints.{name}]").unwrap();
+        for (key, (value, origin)) in options {
+            writeln!(out, "{key} = {value} # {origin}").unwrap();
+        }
+    }
+    out
+}
+
+fn quote(s: &str) -> String {
+    toml::Value::String(s.to_owned()).to_string()
+}
+
+pub mod main {
+    use crate::{
+        config::{ConfigCheck, Configs},
+        err::StatixErr,
+        session,
+    };
+
+    pub fn main(check_config: &ConfigCheck) -> Result<(), StatixErr> {
+        let mut configs = Configs::new(check_config.conf_path.clone());
+        let merged = configs.merged(&check_config.target)?;
+        print!(
+            "{}",
+            super::render(&merged, session::default_nix_version()?)
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,190 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, Read as _},
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::{err::DiffErr, lint::LintResult};
+
+use lib::Report;
+use rnix::{TextRange, TextSize};
+use vfs::ReadOnlyVfs;
+
+/// Lines added or modified since a base revision, per file. Files are
+/// keyed by their canonical path, lines are 1-indexed and inclusive.
+#[derive(Debug, Default)]
+pub struct ChangedLines {
+    files: HashMap<PathBuf, Vec<(usize, usize)>>,
+}
+
+impl ChangedLines {
+    /// Diff the working tree against `base` with git, or read a unified
+    /// diff from stdin if `base` is `-`
+    pub fn new(base: &str) -> Result<Self, DiffErr> {
+        if base == "-" {
+            let mut diff = String::new();
+            io::stdin().read_to_string(&mut diff)?;
+            Ok(Self::parse(&diff, Path::new(".")))
+        } else {
+            let root = git(&["rev-parse", "--show-toplevel"])?;
+            let diff = git(&[
+                "diff",
+                "--no-color",
+                "--no-ext-diff",
+                "--unified=0",
+                "--src-prefix=a/",
+                "--dst-prefix=b/",
+                base,
+            ])?;
+            let root = Path::new(root.trim_end());
+            let mut changed = Self::parse(&diff, root);
+
+            // untracked files are not part of the diff, all of their lines
+            // are new
+            let untracked = git(&[
+                "-C",
+                &root.to_string_lossy(),
+                "ls-files",
+                "-z",
+                "--others",
+                "--exclude-standard",
+            ])?;
+            for path in untracked.split('\0').filter(|path| !path.is_empty()) {
+                if let Ok(path) = fs::canonicalize(root.join(path)) {
+                    changed.files.insert(path, vec![(1, usize::MAX)]);
+                }
+            }
+            Ok(changed)
+        }
+    }
+
+    /// Paths in `diff` are relative to `root`
+    #[must_use]
+    pub fn parse(diff: &str, root: &Path) -> Self {
+        let mut files: HashMap<PathBuf, Vec<(usize, usize)>> = HashMap::new();
+        let mut file = None;
+        let mut lines = diff.lines();
+        while let Some(line) = lines.next() {
+            if let Some(path) = line.strip_prefix("+++ ") {
+                file = new_file(path).and_then(|path| fs::canonicalize(root.join(path)).ok());
+            } else if let Some((mut new_line, new_len, old_len)) = hunk_header(line) {
+                // hunks are walked line by line, as they may carry context
+                let (mut new_left, mut old_left) = (new_len, old_len);
+                while new_left > 0 || old_left > 0 {
+                    let Some(line) = lines.next() else { break };
+                    match line.chars().next() {
+                        Some('+') => {
+                            if let Some(file) = &file {
+                                add_line(files.entry(file.clone()).or_default(), new_line);
+                            }
+                            new_line += 1;
+                            new_left = new_left.saturating_sub(1);
+                        }
+                        Some('-') => old_left = old_left.saturating_sub(1),
+                        Some('\\') => {}
+                        _ => {
+                            new_line += 1;
+                            new_left = new_left.saturating_sub(1);
+                            old_left = old_left.saturating_sub(1);
+                        }
+                    }
+                }
+            }
+        }
+        Self { files }
+    }
+
+    /// Byte ranges of the changed lines of `src`, the contents of `path`
+    #[must_use]
+    pub fn ranges(&self, path: &Path, src: &str) -> Vec<TextRange> {
+        let Some(lines) = fs::canonicalize(path)
+            .ok()
+            .and_then(|path| self.files.get(&path))
+        else {
+            return Vec::new();
+        };
+        let line_starts = std::iter::once(0)
+            .chain(src.match_indices('\n').map(|(i, _)| i + 1))
+            .chain(std::iter::once(src.len()))
+            .collect::<Vec<_>>();
+        let offset = |line: usize| {
+            let offset = line_starts.get(line).copied().unwrap_or(src.len());
+            TextSize::try_from(offset).unwrap_or_default()
+        };
+        lines
+            .iter()
+            .filter(|(start, _)| *start < line_starts.len())
+            .map(|&(start, end)| TextRange::new(offset(start - 1), offset(end)))
+            .collect()
+    }
+
+    /// Remove reports that do not touch changed lines from `results`
+    pub fn filter(&self, results: &mut Vec<LintResult>, vfs: &ReadOnlyVfs) {
+        for result in results.iter_mut() {
+            let path = vfs.file_path(result.file_id);
+            let ranges = self.ranges(path, vfs.get_str(result.file_id));
+            result.reports.retain(|report| touches(report, &ranges));
+        }
+        results.retain(|result| !result.reports.is_empty());
+    }
+}
+
+/// Whether any diagnostic of `report` touches one of `ranges`
+#[must_use]
+pub fn touches(report: &Report, ranges: &[TextRange]) -> bool {
+    report
+        .diagnostics
+        .iter()
+        .any(|d| ranges.iter().any(|&r| overlaps(d.at, r)))
+}
+
+/// Whether `at` touches `range`, empty ranges touch the range they are in
+#[must_use]
+pub fn overlaps(at: TextRange, range: TextRange) -> bool {
+    if at.is_empty() {
+        range.contains(at.start())
+    } else {
+        at.start() < range.end() && range.start() < at.end()
+    }
+}
+
+fn git(args: &[&str]) -> Result<String, DiffErr> {
+    let output = Command::new("git").args(args).output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(DiffErr::Git(stderr.trim().to_owned()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// `+++ b/path`, `+++ path\t<timestamp>` or `+++ /dev/null`
+fn new_file(header: &str) -> Option<&str> {
+    let path = header.split('\t').next()?;
+    if path == "/dev/null" {
+        return None;
+    }
+    Some(path.strip_prefix("b/").unwrap_or(path))
+}
+
+// `@@ -old_start[,old_len] +new_start[,new_len] @@`
+fn hunk_header(line: &str) -> Option<(usize, usize, usize)> {
+    let mut parts = line.strip_prefix("@@ ")?.split(' ');
+    let range = |part: &str| -> Option<(usize, usize)> {
+        match part.split_once(',') {
+            Some((start, len)) => Some((start.parse().ok()?, len.parse().ok()?)),
+            None => Some((part.parse().ok()?, 1)),
+        }
+    };
+    let (_, old_len) = range(parts.next()?.strip_prefix('-')?)?;
+    let (new_start, new_len) = range(parts.next()?.strip_prefix('+')?)?;
+    Some((new_start, new_len, old_len))
+}
+
+fn add_line(ranges: &mut Vec<(usize, usize)>, line: usize) {
+    match ranges.last_mut() {
+        Some((_, end)) if *end + 1 == line => *end = line,
+        _ => ranges.push((line, line)),
+    }
+}
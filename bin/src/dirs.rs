@@ -1,98 +1,106 @@
 use std::{
-    fs,
     io::{self, Error, ErrorKind},
     path::{Path, PathBuf},
 };
 
-use crate::dirs;
-
 use ignore::{
-    Error as IgnoreError, Match,
+    Error as IgnoreError, WalkBuilder,
     gitignore::{Gitignore, GitignoreBuilder},
 };
 
-#[derive(Debug)]
+/// Walks `.nix` files under a target, ignoring files in the same way as
+/// git: `.gitignore` files in the target, its parents and subdirectories,
+/// `.git/info/exclude` and the global `core.excludesFile` are respected,
+/// along with `.ignore` files. Patterns from the command line and config
+/// file are applied on top.
+#[derive(Debug, Clone)]
 pub struct Walker {
-    dirs: Vec<PathBuf>,
-    files: Vec<PathBuf>,
+    target: PathBuf,
     ignore: Gitignore,
+    unrestricted: bool,
 }
 
 impl Walker {
-    pub fn new<P: AsRef<Path>>(target: P, ignore: Gitignore) -> io::Result<Self> {
-        let target = target.as_ref().to_path_buf();
-        if !target.exists() {
-            Err(Error::new(
+    pub fn new<P: AsRef<Path>>(target: P, ignore: Gitignore, unrestricted: bool) -> Self {
+        Self {
+            target: target.as_ref().to_path_buf(),
+            ignore,
+            unrestricted,
+        }
+    }
+
+    #[must_use]
+    pub fn target(&self) -> &Path {
+        &self.target
+    }
+
+    pub fn files(&self) -> io::Result<impl Iterator<Item = PathBuf> + use<>> {
+        if !self.target.exists() {
+            return Err(Error::new(
                 ErrorKind::NotFound,
-                format!("file not found: {}", target.display()),
-            ))
-        } else if target.is_dir() {
-            Ok(Self {
-                dirs: vec![target],
-                files: vec![],
-                ignore,
-            })
-        } else {
-            Ok(Self {
-                dirs: vec![],
-                files: vec![target],
-                ignore,
-            })
+                format!("file not found: {}", self.target.display()),
+            ));
         }
+        let files = self
+            .builder(|_| true)
+            .build()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path().is_file())
+            .map(ignore::DirEntry::into_path)
+            .filter(|path| is_nix(path));
+        Ok(files)
     }
-}
 
-impl Iterator for Walker {
-    type Item = PathBuf;
-    fn next(&mut self) -> Option<Self::Item> {
-        self.files.pop().or_else(|| {
-            while let Some(dir) = self.dirs.pop() {
-                if dir.is_dir()
-                    && let Match::None | Match::Whitelist(_) = self.ignore.matched(&dir, true)
-                {
-                    let mut found = false;
-                    for entry in fs::read_dir(&dir).ok()? {
-                        let entry = entry.ok()?;
-                        let path = entry.path();
-                        if path.is_dir() {
-                            self.dirs.push(path);
-                        } else if path.is_file()
-                            && let Match::None | Match::Whitelist(_) =
-                                self.ignore.matched(&path, false)
-                        {
-                            found = true;
-                            self.files.push(path);
-                        }
-                    }
-                    if found {
-                        break;
-                    }
-                }
-            }
-            self.files.pop()
-        })
+    /// Whether `path` would be produced by `files`. Only the directories
+    /// leading up to `path` are read.
+    #[must_use]
+    pub fn includes(&self, path: &Path) -> bool {
+        if !is_nix(path) {
+            return false;
+        }
+        let target = path.to_path_buf();
+        self.builder(move |entry| target.starts_with(entry.path()))
+            .build()
+            .filter_map(Result::ok)
+            .any(|entry| entry.path() == path && path.is_file())
+    }
+
+    fn builder<F>(&self, filter: F) -> WalkBuilder
+    where
+        F: Fn(&ignore::DirEntry) -> bool + Send + Sync + 'static,
+    {
+        let respect = !self.unrestricted;
+        let ignore = self.ignore.clone();
+        let mut builder = WalkBuilder::new(&self.target);
+        builder
+            .standard_filters(false)
+            .git_ignore(respect)
+            .git_exclude(respect)
+            .git_global(respect)
+            .ignore(respect)
+            .parents(respect)
+            // .gitignore files are respected outside of git repositories too
+            .require_git(false)
+            .follow_links(true)
+            .filter_entry(move |entry| {
+                let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+                !ignore.matched(entry.path(), is_dir).is_ignore() && filter(entry)
+            });
+        builder
     }
 }
 
+/// Build the set of patterns passed through `--ignore` and the config
+/// file, `.gitignore` and friends are handled by the `Walker`
 pub fn build_ignore_set<P: AsRef<Path>>(
     ignore: &[String],
     target: P,
     unrestricted: bool,
 ) -> Result<Gitignore, IgnoreError> {
-    let gitignore_path = target.as_ref().join(".gitignore");
-
-    // Looks like GitignoreBuilder::new does not source globs
-    // within gitignore_path by default, we have to enforce that
-    // using GitignoreBuilder::add. Probably a bug in the ignore
-    // crate?
-    let mut gitignore = GitignoreBuilder::new(&gitignore_path);
+    let mut gitignore = GitignoreBuilder::new(target);
 
-    // if we are to "restrict" aka "respect" .gitignore, then
-    // add globs from gitignore path as well
+    // ignore .git by default, nobody cares about .git, i'm sure
     if !unrestricted {
-        gitignore.add(&gitignore_path);
-
-        // ignore .git by default, nobody cares about .git, i'm sure
         gitignore.add_line(None, ".git")?;
     }
 
@@ -103,10 +111,6 @@ pub fn build_ignore_set<P: AsRef<Path>>(
     gitignore.build()
 }
 
-pub fn walk_nix_files<P: AsRef<Path>>(
-    ignore: Gitignore,
-    target: P,
-) -> Result<impl Iterator<Item = PathBuf>, io::Error> {
-    let walker = dirs::Walker::new(target, ignore)?;
-    Ok(walker.filter(|path: &PathBuf| matches!(path.extension(), Some(e) if e == "nix")))
+pub(crate) fn is_nix(path: &Path) -> bool {
+    matches!(path.extension(), Some(e) if e == "nix")
 }
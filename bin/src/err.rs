@@ -1,4 +1,4 @@
-use std::io;
+use std::{io, path::PathBuf};
 
 use thiserror::Error;
 
@@ -16,6 +16,14 @@ pub enum ConfigErr {
     ConfFileParse(toml::de::Error),
     #[error("unable to parse nix version: `{0}`")]
     ConfFileVersionParse(String),
+    #[error("unable to parse nix version in STATIX_NIX_VERSION: `{0}`")]
+    NixVersionVar(String),
+    #[error("invalid lint option: {0}")]
+    LintOption(String),
+    #[error("invalid config file {}: {message}", .path.display())]
+    InvalidConfFile { path: PathBuf, message: String },
+    #[error("`--files-from -` and `--diff-base -` cannot both read from stdin")]
+    StdinConflict,
 }
 
 // #[derive(Error, Debug)]
@@ -30,6 +38,10 @@ pub enum FixErr {
     // Parse(PathBuf, ParseError),
     #[error("path error: {0}")]
     InvalidPath(#[from] io::Error),
+    #[error("unable to prompt: {0}")]
+    Prompt(io::Error),
+    #[error("unable to write fixes: {0}")]
+    Output(io::Error),
 }
 
 #[derive(Error, Debug)]
@@ -50,6 +62,43 @@ pub enum ExplainErr {
     LintNotFound(u32),
 }
 
+#[derive(Error, Debug)]
+pub enum BaselineErr {
+    #[error("path error: {0}")]
+    InvalidPath(#[from] io::Error),
+    #[error("unable to parse baseline file: {0}")]
+    Parse(serde_json::Error),
+}
+
+#[derive(Error, Debug)]
+pub enum DiffErr {
+    #[error("path error: {0}")]
+    InvalidPath(#[from] io::Error),
+    #[error("git diff failed: {0}")]
+    Git(String),
+}
+
+#[derive(Error, Debug)]
+pub enum WatchErr {
+    #[error("path error: {0}")]
+    InvalidPath(#[from] io::Error),
+    #[error("unable to watch for changes: {0}")]
+    Notify(#[from] notify::Error),
+    #[error("watcher disconnected")]
+    Disconnected,
+}
+
+#[cfg(feature = "lsp")]
+#[derive(Error, Debug)]
+pub enum LspErr {
+    #[error("protocol error: {0}")]
+    Protocol(#[from] lsp_server::ProtocolError),
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("client disconnected")]
+    Disconnected,
+}
+
 #[derive(Error, Debug)]
 pub enum StatixErr {
     // #[error("linter error: {0}")]
@@ -62,4 +111,13 @@ pub enum StatixErr {
     Config(#[from] ConfigErr),
     #[error("explain error: {0}")]
     Explain(#[from] ExplainErr),
+    #[error("baseline error: {0}")]
+    Baseline(#[from] BaselineErr),
+    #[error("diff error: {0}")]
+    Diff(#[from] DiffErr),
+    #[error("watch error: {0}")]
+    Watch(#[from] WatchErr),
+    #[cfg(feature = "lsp")]
+    #[error("language server error: {0}")]
+    Lsp(#[from] LspErr),
 }
@@ -1,5 +1,7 @@
 use crate::{err::ExplainErr, utils};
 
+use lib::{Explain, Metadata as _};
+
 pub fn explain(code: u32) -> Result<&'static str, ExplainErr> {
     let lints = utils::lint_map();
     match code {
@@ -8,7 +10,7 @@ pub fn explain(code: u32) -> Result<&'static str, ExplainErr> {
             .values()
             .flatten()
             .find(|l| l.code() == code)
-            .map(|l| l.explanation())
+            .map(Explain::explanation)
             .ok_or(ExplainErr::LintNotFound(code)),
     }
 }
@@ -2,6 +2,7 @@ use std::borrow::Cow;
 
 use crate::LintMap;
 
+use lib::session::SessionInfo;
 use rnix::TextRange;
 
 mod all;
@@ -10,89 +11,291 @@ use all::all_with;
 mod single;
 use single::single;
 
+mod interactive;
+use interactive::{Prompt, interactive};
+
 type Source<'a> = Cow<'a, str>;
 
+#[derive(Clone)]
 pub struct FixResult<'a> {
     pub src: Source<'a>,
+    /// Fixes applied over all passes, ranges point into the source the
+    /// first pass started from. Fixes that build on one another are merged.
     pub fixed: Vec<Fixed>,
+    /// Fixes that were reverted because they introduced syntax errors,
+    /// ranges point into `src`
+    pub broken: Vec<Fixed>,
+    /// Whether fixes kept undoing each other, and were cut short
+    pub cycle: bool,
     pub lints: &'a LintMap,
+    pub session: &'a SessionInfo,
+    /// If present, only fixes within these ranges of `src` are applied
+    pub within: Option<Vec<TextRange>>,
+    /// Whether fixes that are not machine applicable are applied
+    pub unsafe_fixes: bool,
 }
 
-#[derive(Debug, Clone)]
+/// A fix of a lint, `fix` replaces the source at `at`
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Fixed {
     pub at: TextRange,
     pub code: u32,
+    pub fix: String,
+}
+
+impl Fixed {
+    /// The fix `report` makes to `src`, as a single replacement
+    fn of(report: &lib::Report, src: &str) -> Self {
+        let at = report.range();
+        let mut fix = src[at].to_owned();
+        for edit in report.edits().into_iter().rev() {
+            let start = usize::from(edit.at.start() - at.start());
+            let end = usize::from(edit.at.end() - at.start());
+            fix.replace_range(start..end, &edit.fix.to_string());
+        }
+        Self {
+            at,
+            code: report.code,
+            fix,
+        }
+    }
 }
 
 impl<'a> FixResult<'a> {
-    fn empty(src: Source<'a>, lints: &'a LintMap) -> Self {
+    fn empty(
+        src: Source<'a>,
+        lints: &'a LintMap,
+        session: &'a SessionInfo,
+        within: Option<Vec<TextRange>>,
+        unsafe_fixes: bool,
+    ) -> Self {
         Self {
             src,
             fixed: Vec::new(),
+            broken: Vec::new(),
+            cycle: false,
             lints,
+            session,
+            within,
+            unsafe_fixes,
         }
     }
 }
 
 pub mod main {
-    use std::borrow::Cow;
+    use std::{borrow::Cow, io, path::Path};
 
     use crate::{
-        config::{
-            FixOut, Single as SingleConfig, {ConfFile, Fix as FixConfig},
-        },
+        LintMap,
+        config::{Configs, Fix as FixConfig, FixOut, Single as SingleConfig},
+        diff::ChangedLines,
         err::{FixErr, StatixErr},
+        traits::write_fixes,
+        watch::{Change, Watch},
     };
 
+    use super::{FixResult, Prompt};
+
+    use lib::{LINTS, session::SessionInfo};
+
     use similar::TextDiff;
+    use vfs::VfsEntry;
 
     pub fn all(fix_config: &FixConfig) -> Result<(), StatixErr> {
-        let conf_file = ConfFile::discover(&fix_config.conf_path)?;
-        let vfs = fix_config.vfs(conf_file.ignore.as_slice())?;
+        let mut configs = Configs::new(fix_config.conf_path.clone());
+        let walkers = fix_config.walkers(&mut configs)?;
+        let mut vfs = fix_config.vfs(&walkers, &mut configs)?;
+        let mut changed_lines = fix_config
+            .diff_base
+            .as_deref()
+            .map(ChangedLines::new)
+            .transpose()?;
 
-        let lints = conf_file.lints();
+        if fix_config.interactive {
+            let mut prompt = Prompt::new(io::stdin().lock(), io::stdout());
+            for entry in vfs.iter() {
+                let lints = configs.lints(entry.file_id);
+                let session = configs.session(entry.file_id);
+                let within = changed_lines
+                    .as_ref()
+                    .map(|c| c.ranges(entry.file_path, entry.contents));
+                let fixed = super::interactive(
+                    entry.file_path,
+                    entry.contents,
+                    lints,
+                    session,
+                    fix_config.unsafe_fixes,
+                    within,
+                    &mut prompt,
+                )
+                .map_err(FixErr::Prompt)?;
+                if let Some(fixed) = fixed {
+                    std::fs::write(entry.file_path, fixed).map_err(FixErr::InvalidPath)?;
+                }
+                if prompt.quit {
+                    break;
+                }
+            }
+            return Ok(());
+        }
 
         for entry in vfs.iter() {
-            match (fix_config.out(), super::all_with(entry.contents, &lints)) {
-                (FixOut::Diff, fix_result) => {
-                    let src = fix_result
-                        .map(|r| r.src)
-                        .unwrap_or(Cow::Borrowed(entry.contents));
-                    let text_diff = TextDiff::from_lines(entry.contents, &src);
-                    let old_file = format!("{}", entry.file_path.display());
-                    let new_file = format!("{} [fixed]", entry.file_path.display());
-                    println!(
-                        "{}",
-                        text_diff
-                            .unified_diff()
-                            .context_radius(4)
-                            .header(&old_file, &new_file)
-                    );
-                }
-                (FixOut::Stream, fix_result) => {
-                    let src = fix_result
-                        .map(|r| r.src)
-                        .unwrap_or(Cow::Borrowed(entry.contents));
-                    println!("{}", &src);
+            let lints = configs.lints(entry.file_id);
+            let session = configs.session(entry.file_id);
+            fix_entry(fix_config, &entry, lints, session, changed_lines.as_ref())?;
+        }
+
+        if fix_config.watch {
+            let watch = Watch::new(walkers)?;
+            loop {
+                let changes = watch.changes()?;
+                // a diff read from stdin cannot be read again
+                if let Some(base) = fix_config.diff_base.as_deref().filter(|&b| b != "-")
+                    && let Ok(changed) = ChangedLines::new(base)
+                {
+                    changed_lines = Some(changed);
                 }
-                (FixOut::Write, Some(fix_result)) => {
-                    let path = entry.file_path;
-                    std::fs::write(path, &*fix_result.src).map_err(FixErr::InvalidPath)?;
+                for change in changes {
+                    match change {
+                        Change::Modified(path) => {
+                            // files may be ignored by config files below
+                            // the targets
+                            if configs.ignored(&path).unwrap_or(true) {
+                                continue;
+                            }
+                            let Ok(contents) = std::fs::read_to_string(&path) else {
+                                continue;
+                            };
+                            vfs.set_file_contents(&path, contents.as_bytes());
+                            let file_id = vfs.alloc_file_id(&path);
+                            if configs.assign(file_id, &path).is_err() {
+                                continue;
+                            }
+                            let lints = configs.lints(file_id);
+                            let session = configs.session(file_id);
+                            let entry = vfs.entry(file_id);
+                            fix_entry(fix_config, &entry, lints, session, changed_lines.as_ref())?;
+                        }
+                        Change::Removed(path) => {
+                            if let Some(file_id) = vfs.file_id(&path) {
+                                vfs.remove_file(file_id);
+                            }
+                        }
+                    }
                 }
-                _ => (),
             }
         }
         Ok(())
     }
 
+    /// Fix `entry` and output the result as `fix_config` asks for
+    pub fn fix_entry(
+        fix_config: &FixConfig,
+        entry: &VfsEntry,
+        lints: &LintMap,
+        session: &SessionInfo,
+        changed: Option<&ChangedLines>,
+    ) -> Result<(), StatixErr> {
+        let within = changed.map(|c| c.ranges(entry.file_path, entry.contents));
+        let fix_result = super::all_with(
+            entry.contents,
+            lints,
+            session,
+            within,
+            fix_config.unsafe_fixes,
+        );
+        if let Some(fix_result) = &fix_result {
+            report_bugs(entry.file_path, fix_result);
+        }
+        // the source is left as is when fixes undo each other
+        let fix_result = fix_result.filter(|fix_result| !fix_result.cycle);
+        match (fix_config.out(), fix_result) {
+            (FixOut::Diff, fix_result) => {
+                let src = fix_result
+                    .map(|r| r.src)
+                    .unwrap_or(Cow::Borrowed(entry.contents));
+                let text_diff = TextDiff::from_lines(entry.contents, &src);
+                let old_file = format!("{}", entry.file_path.display());
+                let new_file = format!("{} [fixed]", entry.file_path.display());
+                println!(
+                    "{}",
+                    text_diff
+                        .unified_diff()
+                        .context_radius(4)
+                        .header(&old_file, &new_file)
+                );
+            }
+            (FixOut::Stream, fix_result) => {
+                let src = fix_result
+                    .map(|r| r.src)
+                    .unwrap_or(Cow::Borrowed(entry.contents));
+                println!("{}", &src);
+            }
+            (FixOut::Write, Some(fix_result)) => {
+                let path = entry.file_path;
+                std::fs::write(path, &*fix_result.src).map_err(FixErr::InvalidPath)?;
+            }
+            (FixOut::Json, Some(fix_result)) => {
+                let mut stdout = io::stdout();
+                write_fixes(
+                    &mut stdout,
+                    entry.file_path,
+                    entry.contents,
+                    &fix_result.fixed,
+                )
+                .map_err(FixErr::Output)?;
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
+    // fixes that break the source, or that undo each other are bugs in
+    // lints, they are reported instead of being written
+    fn report_bugs(path: &Path, fix_result: &FixResult) {
+        for broken in &fix_result.broken {
+            let name = LINTS
+                .iter()
+                .find(|l| l.code() == broken.code)
+                .map_or("unknown", |l| l.name());
+            let line = fix_result.src[..usize::from(broken.at.start())]
+                .matches('\n')
+                .count()
+                + 1;
+            eprintln!(
+                "{}:{line}: fix for `{name}` (W{:02}) introduces a syntax error and was not applied, this is a bug in statix",
+                path.display(),
+                broken.code
+            );
+        }
+        if fix_result.cycle {
+            eprintln!(
+                "{}: fixes do not converge, this is a bug in statix",
+                path.display()
+            );
+        }
+    }
+
     pub fn single(single_config: &SingleConfig) -> Result<(), StatixErr> {
         let vfs = single_config.vfs()?;
         let entry = vfs.iter().next().unwrap();
         let path = entry.file_path.display().to_string();
         let original_src = entry.contents;
         let (line, col) = single_config.position;
+        // a file on stdin uses the config file of the working directory
+        let target = match &single_config.target {
+            Some(target) if !single_config.streaming => target.as_path(),
+            _ => Path::new("."),
+        };
+        let mut configs = Configs::new(single_config.conf_path.clone());
+        configs.assign(entry.file_id, target)?;
+        let lints = configs.lints(entry.file_id);
+        let session = configs.session(entry.file_id);
 
-        match (single_config.out(), super::single(line, col, original_src)) {
+        match (
+            single_config.out(),
+            super::single(line, col, original_src, lints, session),
+        ) {
             (FixOut::Diff, single_result) => {
                 let fixed_src = single_result
                     .map(|r| r.src)
@@ -118,6 +321,8 @@ pub mod main {
                 let path = entry.file_path;
                 std::fs::write(path, &*single_result.src).map_err(FixErr::InvalidPath)?;
             }
+            // `single` has no `--format` flag
+            (FixOut::Json, Ok(_)) => unreachable!(),
             (_, Err(e)) => return Err(e.into()),
         }
         Ok(())
@@ -1,16 +1,23 @@
 use std::borrow::Cow;
 
-use lib::Report;
-use rnix::{Root, WalkEvent, parser::ParseError as RnixParseErr};
+use lib::{Applicability, Context, Report, Rule as _, Suppressions, session::SessionInfo};
+use rnix::{Root, TextRange, TextSize, WalkEvent, parser::ParseError as RnixParseErr};
 use rowan::ast::AstNode as _;
 
 use crate::{
-    LintMap,
+    LintMap, diff,
     fix::{FixResult, Fixed},
 };
 
-fn collect_fixes(source: &str, lints: &LintMap) -> Result<Vec<Report>, RnixParseErr> {
+pub(super) fn collect_fixes(
+    source: &str,
+    lints: &LintMap,
+    session: &SessionInfo,
+    unsafe_fixes: bool,
+) -> Result<Vec<Report>, RnixParseErr> {
     let parsed = Root::parse(source).ok()?;
+    let suppressions = Suppressions::new(parsed.syntax());
+    let ctx = Context::new(parsed.syntax(), session);
 
     Ok(parsed
         .syntax()
@@ -19,8 +26,13 @@ fn collect_fixes(source: &str, lints: &LintMap) -> Result<Vec<Report>, RnixParse
             WalkEvent::Enter(child) => lints.get(&child.kind()).map(|rules| {
                 rules
                     .iter()
-                    .filter_map(|rule| rule.validate(&child))
-                    .filter(|report| report.total_suggestion_range().is_some())
+                    .filter_map(|rule| rule.validate(&child, &ctx))
+                    .filter(|report| match report.applicability() {
+                        Some(Applicability::MachineApplicable) => true,
+                        Some(_) => unsafe_fixes,
+                        None => false,
+                    })
+                    .filter(|report| !suppressions.suppresses(report))
                     .collect::<Vec<_>>()
             }),
             WalkEvent::Leave(_) => None,
@@ -29,63 +41,251 @@ fn collect_fixes(source: &str, lints: &LintMap) -> Result<Vec<Report>, RnixParse
         .collect())
 }
 
-fn reorder(mut reports: Vec<Report>) -> Vec<Report> {
-    use std::collections::VecDeque;
-
-    reports.sort_by(|a, b| {
-        let a_range = a.range();
-        let b_range = b.range();
-        a_range.end().partial_cmp(&b_range.end()).unwrap()
-    });
-
-    reports
-        .into_iter()
-        .fold(VecDeque::new(), |mut deque: VecDeque<Report>, new_elem| {
-            let front = deque.front();
-            let new_range = new_elem.range();
-            if let Some(front_range) = front.map(lib::Report::range) {
-                if new_range.start() > front_range.end() {
-                    deque.push_front(new_elem);
-                }
-            } else {
-                deque.push_front(new_elem);
-            }
-            deque
-        })
-        .into()
+/// Pick reports whose edits do not touch one another, listed back to front
+pub(super) fn reorder(mut reports: Vec<Report>) -> Vec<Report> {
+    reports.sort_by_key(|r| r.range().end());
+
+    let mut picked: Vec<Report> = Vec::new();
+    for report in reports {
+        let conflicts = report.edits().into_iter().any(|edit| {
+            picked
+                .iter()
+                .flat_map(Report::edits)
+                .any(|other| edit.at.start() <= other.at.end() && other.at.start() <= edit.at.end())
+        });
+        if !conflicts {
+            picked.push(report);
+        }
+    }
+    picked.sort_by_key(|r| std::cmp::Reverse(r.range().start()));
+    picked
 }
 
-impl<'a> Iterator for FixResult<'a> {
-    type Item = FixResult<'a>;
+impl Iterator for FixResult<'_> {
+    type Item = Self;
     fn next(&mut self) -> Option<Self::Item> {
-        let all_reports = collect_fixes(&self.src, self.lints).ok()?;
+        let mut all_reports =
+            collect_fixes(&self.src, self.lints, self.session, self.unsafe_fixes).ok()?;
+        if let Some(within) = &self.within {
+            all_reports.retain(|r| diff::touches(r, within));
+        }
+        all_reports.retain(|r| !self.broken.contains(&Fixed::of(r, &self.src)));
         if all_reports.is_empty() {
             return None;
         }
 
-        let reordered = reorder(all_reports);
-        let fixed = reordered
-            .iter()
-            .map(|r| Fixed {
-                at: r.range(),
-                code: r.code,
-            })
-            .collect::<Vec<_>>();
-        for report in reordered {
-            report.apply(self.src.to_mut());
+        let mut src = self.src.to_string();
+        let (within, previous) = (&mut self.within, &mut self.broken);
+        let (fixed, broken) = apply_reports(&mut src, reorder(all_reports), |report| {
+            let ranges = within.iter_mut().flatten();
+            let previous = previous.iter_mut().map(|b| &mut b.at);
+            for range in ranges.chain(previous) {
+                shift(range, report);
+            }
+        });
+        self.src = Cow::Owned(src);
+        self.broken.extend(broken);
+        for fixed in fixed {
+            merge(&mut self.fixed, fixed);
         }
 
-        Some(FixResult {
-            src: self.src.clone(),
-            fixed,
-            lints: self.lints,
-        })
+        Some(self.clone())
+    }
+}
+
+/// Apply non-conflicting `reports` to `src`, skipping those that introduce
+/// syntax errors. Lints are expected to produce valid fixes, a fix that
+/// does not reparse is a bug in the lint. `on_apply` is called with every
+/// report that is applied, so that ranges held by the caller can be
+/// shifted. Returns the applied and the skipped fixes, the ranges of
+/// skipped fixes point into the final `src`.
+pub(super) fn apply_reports(
+    src: &mut String,
+    reports: Vec<Report>,
+    mut on_apply: impl FnMut(&Report),
+) -> (Vec<Fixed>, Vec<Fixed>) {
+    let mut applied = Applied::default();
+    apply_batch(src, reports, &mut applied);
+    for report in &applied.reports {
+        on_apply(report);
     }
+    (applied.fixed, applied.broken)
 }
 
-pub fn all_with<'a>(src: &'a str, lints: &'a LintMap) -> Option<FixResult<'a>> {
+#[derive(Default)]
+struct Applied {
+    reports: Vec<Report>,
+    fixed: Vec<Fixed>,
+    broken: Vec<Fixed>,
+}
+
+/// Apply `reports` one after another and parse the result once. If it does
+/// not parse, each half of `reports` is applied on its own, until the
+/// reports that break the source are found.
+fn apply_batch(src: &mut String, reports: Vec<Report>, applied: &mut Applied) {
+    if reports.is_empty() {
+        return;
+    }
+
+    let mut batch = reports.clone();
+    let mut candidate = src.clone();
+    let mut fixed = Vec::with_capacity(batch.len());
+    for i in 0..batch.len() {
+        let (report, rest) = batch[i..].split_first_mut().unwrap();
+        fixed.push(Fixed::of(report, &candidate));
+        report.apply(&mut candidate);
+        for other in rest {
+            shift_report(other, report);
+        }
+    }
+    if Root::parse(&candidate).ok().is_ok() {
+        *src = candidate;
+        for report in &batch {
+            for broken in &mut applied.broken {
+                shift(&mut broken.at, report);
+            }
+        }
+        applied.fixed.extend(fixed);
+        applied.reports.extend(batch);
+        return;
+    }
+
+    if let [report] = reports.as_slice() {
+        applied.broken.push(Fixed::of(report, src));
+        return;
+    }
+    let mut second = reports;
+    let first = second.drain(..second.len() / 2).collect();
+    let before = applied.reports.len();
+    apply_batch(src, first, applied);
+    for report in &applied.reports[before..] {
+        for other in &mut second {
+            shift_report(other, report);
+        }
+    }
+    apply_batch(src, second, applied);
+}
+
+// edits of different reports may interleave, those that are yet to be
+// applied are moved along
+fn shift_report(other: &mut Report, report: &Report) {
+    for d in &mut other.diagnostics {
+        shift(&mut d.at, report);
+        for edit in d.suggestion.iter_mut().flat_map(|s| &mut s.edits) {
+            shift(&mut edit.at, report);
+        }
+    }
+}
+
+/// Record `fix`, whose range points into the source with `edits` applied,
+/// as an edit of the original source. A fix that touches earlier edits is
+/// merged with them, and listed under the lint of the first.
+fn merge(edits: &mut Vec<Fixed>, fix: Fixed) {
+    let (start, end) = (usize::from(fix.at.start()), usize::from(fix.at.end()));
+
+    // where each edit is in the current source
+    let mut current = Vec::with_capacity(edits.len());
+    let mut offset = 0isize;
+    for edit in edits.iter() {
+        let at_start = usize::from(edit.at.start())
+            .checked_add_signed(offset)
+            .unwrap();
+        current.push((at_start, at_start + edit.fix.len()));
+        offset += edit.fix.len().cast_signed() - usize::from(edit.at.len()).cast_signed();
+    }
+    let touched = current
+        .iter()
+        .enumerate()
+        .filter(|(_, (s, e))| *s <= end && start <= *e)
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+
+    let (Some(&first), Some(&last)) = (touched.first(), touched.last()) else {
+        let index = current.partition_point(|(_, e)| *e <= start);
+        let offset = edits[..index]
+            .iter()
+            .map(|e| e.fix.len().cast_signed() - usize::from(e.at.len()).cast_signed())
+            .sum::<isize>();
+        let at_start = start.checked_add_signed(-offset).unwrap();
+        let at = TextRange::at(to_size(at_start), fix.at.len());
+        edits.insert(index, Fixed { at, ..fix });
+        return;
+    };
+
+    let (first_start, _) = current[first];
+    let (last_start, last_end) = current[last];
+    let mut text = edits[first].fix[..start.saturating_sub(first_start)].to_owned();
+    text.push_str(&fix.fix);
+    if end < last_end {
+        text.push_str(&edits[last].fix[end - last_start..]);
+    }
+    let at_start = usize::from(edits[first].at.start()) - first_start.saturating_sub(start);
+    let at_end = usize::from(edits[last].at.end()) + end.saturating_sub(last_end);
+    let merged = Fixed {
+        at: TextRange::new(to_size(at_start), to_size(at_end)),
+        code: edits[first].code,
+        fix: text,
+    };
+    edits.splice(first..=last, [merged]);
+}
+
+fn to_size(offset: usize) -> TextSize {
+    TextSize::try_from(offset).unwrap()
+}
+
+/// Keep `range` pointing at the same source as the edits of `report` are
+/// applied. Ranges that overlap an edit are stretched over its
+/// replacement.
+pub(super) fn shift(range: &mut TextRange, report: &Report) {
+    // back to front, the ranges of earlier edits are left as is
+    for edit in report.edits().into_iter().rev() {
+        let at = edit.at;
+        let len = TextSize::of(edit.fix.to_string().as_str());
+        let start = if range.start() >= at.end() {
+            range.start() - at.end() + at.start() + len
+        } else {
+            range.start().min(at.start())
+        };
+        let end = if range.end() >= at.end() {
+            range.end() - at.end() + at.start() + len
+        } else if range.end() > at.start() {
+            at.start() + len
+        } else {
+            range.end()
+        };
+        *range = TextRange::new(start, end);
+    }
+}
+
+pub fn all_with<'a>(
+    src: &'a str,
+    lints: &'a LintMap,
+    session: &'a SessionInfo,
+    within: Option<Vec<TextRange>>,
+    unsafe_fixes: bool,
+) -> Option<FixResult<'a>> {
     let src = Cow::from(src);
     let _ = Root::parse(&src).ok().ok()?;
-    let initial = FixResult::empty(src, lints);
-    initial.into_iter().last()
+    let initial = FixResult::empty(src, lints, session, within, unsafe_fixes);
+
+    // hare & tortoise: the hare takes two passes for every pass of the
+    // tortoise, if fixes undo each other, the two meet. Sources are
+    // compared along with broken fixes, as a pass that only reverts
+    // broken fixes leaves the source as is.
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    let mut passes = 0;
+    loop {
+        for _ in 0..2 {
+            if hare.next().is_none() {
+                return (passes > 0).then_some(hare);
+            }
+            passes += 1;
+        }
+        tortoise.next();
+        if tortoise.src == hare.src && tortoise.broken == hare.broken {
+            hare.cycle = true;
+            return Some(hare);
+        }
+    }
 }
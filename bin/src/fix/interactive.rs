@@ -0,0 +1,205 @@
+use std::{
+    collections::HashSet,
+    io::{self, BufRead, Write},
+    path::Path,
+};
+
+use ariadne::{Color, Fmt};
+use lib::{Applicability, Metadata as _, Report, session::SessionInfo};
+use rnix::TextRange;
+use similar::{ChangeTag, TextDiff};
+
+use crate::{
+    LintMap, diff,
+    fix::all::{apply_reports, collect_fixes, reorder, shift},
+};
+
+#[derive(Clone, Copy)]
+enum Answer {
+    Apply,
+    Skip,
+    ApplyAll,
+    Quit,
+}
+
+/// Asks whether each fix should be applied. Answers carry over from one
+/// file to the next, a lint accepted with "all" is not asked about again.
+pub struct Prompt<R, W> {
+    input: R,
+    output: W,
+    always: HashSet<u32>,
+    pub quit: bool,
+}
+
+impl<R: BufRead, W: Write> Prompt<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        Self {
+            input,
+            output,
+            always: HashSet::new(),
+            quit: false,
+        }
+    }
+
+    fn ask(
+        &mut self,
+        path: &Path,
+        src: &str,
+        report: &Report,
+        lints: &LintMap,
+    ) -> io::Result<bool> {
+        if self.always.contains(&report.code) {
+            return Ok(true);
+        }
+        let name = lints
+            .values()
+            .flatten()
+            .find(|l| l.code() == report.code)
+            .map_or("syntax_error", |l| l.name());
+        self.show(path, src, report, name)?;
+
+        loop {
+            write!(
+                self.output,
+                "Apply this fix? [y]es, [n]o, [a]ll fixes of {name}, [q]uit: "
+            )?;
+            self.output.flush()?;
+            let mut line = String::new();
+            if self.input.read_line(&mut line)? == 0 {
+                writeln!(self.output)?;
+                return Ok(self.answer(Answer::Quit, report));
+            }
+            let answer = match line.trim().to_ascii_lowercase().as_str() {
+                "y" | "yes" => Answer::Apply,
+                "" | "n" | "no" => Answer::Skip,
+                "a" | "all" => Answer::ApplyAll,
+                "q" | "quit" => Answer::Quit,
+                _ => continue,
+            };
+            return Ok(self.answer(answer, report));
+        }
+    }
+
+    fn answer(&mut self, answer: Answer, report: &Report) -> bool {
+        match answer {
+            Answer::Apply => true,
+            Answer::Skip => false,
+            Answer::ApplyAll => {
+                self.always.insert(report.code);
+                true
+            }
+            Answer::Quit => {
+                self.quit = true;
+                false
+            }
+        }
+    }
+
+    fn broken(&mut self, code: u32) -> io::Result<()> {
+        writeln!(
+            self.output,
+            "The fix for W{code:02} introduces a syntax error and was not applied, this is a bug in statix"
+        )
+    }
+
+    // the location, lint and message of the fix, followed by its hunk
+    fn show(&mut self, path: &Path, src: &str, report: &Report, name: &str) -> io::Result<()> {
+        let at = report.range();
+        let line = src[..usize::from(at.start())].matches('\n').count() + 1;
+        let message = report
+            .diagnostics
+            .iter()
+            .find(|d| d.suggestion.is_some())
+            .map_or(report.note, |d| d.message.as_str());
+        let applicability = match report.applicability() {
+            Some(Applicability::MachineApplicable) | None => String::new(),
+            Some(applicability) => format!(" ({applicability})"),
+        };
+        writeln!(
+            self.output,
+            "{}:{line} [W{:02}] {}{}",
+            path.display(),
+            report.code,
+            name.fg(Color::Magenta),
+            applicability.fg(Color::Yellow)
+        )?;
+        writeln!(self.output, "{message}")?;
+
+        let mut fixed = src.to_owned();
+        report.apply(&mut fixed);
+        let diff = TextDiff::from_lines(src, &fixed);
+        for hunk in diff.unified_diff().context_radius(2).iter_hunks() {
+            writeln!(self.output, "{}", hunk.header().fg(Color::Cyan))?;
+            for change in hunk.iter_changes() {
+                let line = change.to_string_lossy();
+                let line = line.strip_suffix('\n').unwrap_or(&line);
+                let line = match change.tag() {
+                    ChangeTag::Delete => format!("-{line}").fg(Color::Red).to_string(),
+                    ChangeTag::Insert => format!("+{line}").fg(Color::Green).to_string(),
+                    ChangeTag::Equal => format!(" {line}"),
+                };
+                writeln!(self.output, "{line}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Fix `src`, asking before each fix is applied. Returns the fixed source
+/// if any fix was accepted.
+pub fn interactive<R: BufRead, W: Write>(
+    path: &Path,
+    src: &str,
+    lints: &LintMap,
+    session: &SessionInfo,
+    unsafe_fixes: bool,
+    mut within: Option<Vec<TextRange>>,
+    prompt: &mut Prompt<R, W>,
+) -> io::Result<Option<String>> {
+    let mut src = src.to_owned();
+    let mut skipped: Vec<(u32, TextRange)> = Vec::new();
+    let mut changed = false;
+
+    // fixes may overlap, or only appear after others are applied, so
+    // fixes are collected again after each round of answers. Fixes that
+    // are not machine applicable are only asked about with `unsafe_fixes`.
+    while !prompt.quit {
+        let Ok(mut reports) = collect_fixes(&src, lints, session, unsafe_fixes) else {
+            break;
+        };
+        reports.retain(|r| !skipped.contains(&(r.code, r.range())));
+        if let Some(within) = &within {
+            reports.retain(|r| diff::touches(r, within));
+        }
+        if reports.is_empty() {
+            break;
+        }
+
+        // `reorder` lists reports back to front, they are asked about in
+        // the order they appear in, but applied back to front
+        let mut accepted = Vec::new();
+        for report in reorder(reports).into_iter().rev() {
+            if prompt.ask(path, &src, &report, lints)? {
+                accepted.push(report);
+            } else if prompt.quit {
+                break;
+            } else {
+                skipped.push((report.code, report.range()));
+            }
+        }
+        accepted.reverse();
+        let (fixed, broken) = apply_reports(&mut src, accepted, |report| {
+            let ranges = within.iter_mut().flatten();
+            for range in ranges.chain(skipped.iter_mut().map(|(_, range)| range)) {
+                shift(range, report);
+            }
+        });
+        for broken in broken {
+            prompt.broken(broken.code)?;
+            skipped.push((broken.code, broken.at));
+        }
+        changed |= !fixed.is_empty();
+    }
+
+    Ok(changed.then_some(src))
+}
@@ -1,9 +1,9 @@
 use std::{borrow::Cow, convert::TryFrom};
 
-use lib::Report;
+use lib::{Context, Report, Rule as _, session::SessionInfo};
 use rnix::{Root, TextSize, WalkEvent};
 
-use crate::{err::SingleFixErr, fix::Source, utils};
+use crate::{LintMap, err::SingleFixErr, fix::Source};
 
 pub struct SingleFixResult<'δ> {
     pub src: Source<'δ>,
@@ -27,10 +27,15 @@ fn pos_to_byte(line: usize, col: usize, src: &str) -> Result<TextSize, SingleFix
     }
 }
 
-fn find(offset: TextSize, src: &str) -> Result<Report, SingleFixErr> {
+fn find(
+    offset: TextSize,
+    src: &str,
+    lints: &LintMap,
+    session: &SessionInfo,
+) -> Result<Report, SingleFixErr> {
     // we don't really need the source to form a completely parsed tree
     let parsed = Root::parse(src);
-    let lints = utils::lint_map();
+    let ctx = Context::new(&parsed.syntax(), session);
 
     parsed
         .syntax()
@@ -39,7 +44,7 @@ fn find(offset: TextSize, src: &str) -> Result<Report, SingleFixErr> {
             WalkEvent::Enter(child) => lints.get(&child.kind()).map(|rules| {
                 rules
                     .iter()
-                    .filter_map(|rule| rule.validate(&child))
+                    .filter_map(|rule| rule.validate(&child, &ctx))
                     .find(|report| report.total_suggestion_range().is_some())
             }),
             WalkEvent::Leave(_) => None,
@@ -49,10 +54,16 @@ fn find(offset: TextSize, src: &str) -> Result<Report, SingleFixErr> {
         .ok_or(SingleFixErr::NoOp)
 }
 
-pub fn single(line: usize, col: usize, src: &str) -> Result<SingleFixResult<'_>, SingleFixErr> {
+pub fn single<'a>(
+    line: usize,
+    col: usize,
+    src: &'a str,
+    lints: &LintMap,
+    session: &SessionInfo,
+) -> Result<SingleFixResult<'a>, SingleFixErr> {
     let mut src = Cow::from(src);
     let offset = pos_to_byte(line, col, &src)?;
-    let report = find(offset, &src)?;
+    let report = find(offset, &src, lints, session)?;
 
     report.apply(src.to_mut());
 
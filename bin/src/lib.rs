@@ -1,4 +1,7 @@
+pub mod baseline;
 pub mod config;
+pub mod config_check;
+pub mod diff;
 pub mod dirs;
 pub mod dump;
 pub mod err;
@@ -6,14 +9,16 @@ pub mod explain;
 pub mod fix;
 pub mod lint;
 pub mod list;
+#[cfg(feature = "lsp")]
+pub mod lsp;
 pub mod session;
 pub mod traits;
+pub mod watch;
 
-mod utils;
+pub mod utils;
 
 use std::collections::HashMap;
 
-use lib::Lint;
 use rnix::SyntaxKind;
 
-pub type LintMap = HashMap<SyntaxKind, Vec<&'static Box<dyn Lint>>>;
+pub type LintMap = HashMap<SyntaxKind, Vec<utils::ConfiguredLint>>;
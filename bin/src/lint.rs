@@ -1,7 +1,9 @@
 use crate::LintMap;
 
-use lib::Report;
-use rnix::{Root, WalkEvent};
+use lib::{
+    Context, Metadata as _, Report, Rule as _, Severity, Suppressions, session::SessionInfo,
+};
+use rnix::{Root, SyntaxKind, WalkEvent};
 use vfs::{FileId, VfsEntry};
 
 #[derive(Debug)]
@@ -11,10 +13,11 @@ pub struct LintResult {
 }
 
 #[must_use]
-pub fn lint_with(vfs_entry: &VfsEntry, lints: &LintMap) -> LintResult {
+pub fn lint_with(vfs_entry: &VfsEntry, lints: &LintMap, session: &SessionInfo) -> LintResult {
     let file_id = vfs_entry.file_id;
     let source = vfs_entry.contents;
     let parsed = Root::parse(source);
+    let ctx = Context::new(&parsed.syntax(), session);
 
     let error_reports = parsed
         .errors()
@@ -27,7 +30,7 @@ pub fn lint_with(vfs_entry: &VfsEntry, lints: &LintMap) -> LintResult {
             WalkEvent::Enter(child) => lints.get(&child.kind()).map(|rules| {
                 rules
                     .iter()
-                    .filter_map(|rule| rule.validate(&child))
+                    .filter_map(|rule| rule.validate(&child, &ctx))
                     .collect::<Vec<_>>()
             }),
             WalkEvent::Leave(_) => None,
@@ -36,42 +39,229 @@ pub fn lint_with(vfs_entry: &VfsEntry, lints: &LintMap) -> LintResult {
         .chain(error_reports)
         .collect();
 
+    let suppressions = Suppressions::new(&parsed.syntax());
+    let (mut reports, suppressed) = suppressions.partition(reports);
+    reports.extend(unused_suppressions(&suppressions, &suppressed, lints));
+    // reports are listed in the order they appear in, regardless of the
+    // order lints ran in
+    reports.sort_by_key(|r| {
+        let at = r.total_diagnostic_range().unwrap_or_default();
+        (at.start(), at.end(), r.code)
+    });
+
     LintResult { file_id, reports }
 }
 
+// unused suppressions are raised under the `unused_suppression` lint, only
+// if it is enabled, and only for lints that are enabled
+fn unused_suppressions(
+    suppressions: &Suppressions,
+    suppressed: &[Report],
+    lints: &LintMap,
+) -> Vec<Report> {
+    let Some(unused_lint) = lints
+        .get(&SyntaxKind::TOKEN_COMMENT)
+        .into_iter()
+        .flatten()
+        .find(|l| l.name() == "unused_suppression")
+    else {
+        return Vec::new();
+    };
+    let is_enabled = |code| lints.values().flatten().any(|l| l.code() == code);
+    suppressions
+        .unused(suppressed)
+        .filter(|allowed| allowed.code.is_some_and(is_enabled))
+        .map(|allowed| {
+            unused_lint.report().diagnostic(
+                allowed.at,
+                format!(
+                    "`{}` is not raised here, consider removing it",
+                    allowed.name
+                ),
+            )
+        })
+        .collect()
+}
+
+/// Exit status of `statix check`. Failures within statix itself, such as
+/// an invalid config file, exit with 2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// Nothing was denied
+    Pass = 0,
+    /// At least one report has `Error` severity
+    Errors = 1,
+    /// No errors, but warnings or hints were denied
+    Warnings = 3,
+}
+
+impl Outcome {
+    /// Reports of severity `deny` or higher fail the check, warnings fail
+    /// it only if there are more than `max_warnings` of them
+    #[must_use]
+    pub fn of(results: &[LintResult], deny: Severity, max_warnings: usize) -> Self {
+        let count = |severity| {
+            results
+                .iter()
+                .flat_map(|r| &r.reports)
+                .filter(|r| r.severity == severity)
+                .count()
+        };
+        if count(Severity::Error) > 0 {
+            Self::Errors
+        } else if (deny <= Severity::Warn && count(Severity::Warn) > max_warnings)
+            || (deny <= Severity::Hint && count(Severity::Hint) > 0)
+        {
+            Self::Warnings
+        } else {
+            Self::Pass
+        }
+    }
+}
+
 pub mod main {
-    use std::io;
+    use std::{fs, io};
 
-    use super::lint_with;
+    use super::{LintResult, Outcome, lint_with};
     use crate::{
-        config::{Check as CheckConfig, ConfFile},
+        baseline::Baseline,
+        config::{Check as CheckConfig, Configs},
+        diff::ChangedLines,
         err::StatixErr,
         traits::WriteDiagnostic,
+        watch::{Change, Watch},
     };
 
     use rayon::prelude::*;
+    use vfs::{ReadOnlyVfs, VfsEntry};
 
     pub fn main(check_config: &CheckConfig) -> Result<(), StatixErr> {
-        let conf_file = ConfFile::discover(&check_config.conf_path)?;
-        let lints = conf_file.lints();
-
-        let vfs = check_config.vfs(conf_file.ignore.as_slice())?;
+        let mut configs = Configs::new(check_config.conf_path.clone());
+        let walkers = check_config.walkers(&mut configs)?;
+        let vfs = check_config.vfs(&walkers, &mut configs)?;
 
         let mut stdout = io::stdout();
-        let lint = |vfs_entry| lint_with(&vfs_entry, &lints);
-        let results = vfs
+        let lint = |vfs_entry: VfsEntry| {
+            let lints = configs.lints(vfs_entry.file_id);
+            let session = configs.session(vfs_entry.file_id);
+            lint_with(&vfs_entry, lints, session)
+        };
+        let mut results = vfs
             .par_iter()
             .map(lint)
             .filter(|lr| !lr.reports.is_empty())
             .collect::<Vec<_>>();
 
-        if !results.is_empty() {
-            for r in &results {
-                stdout.write(r, &vfs, check_config.format).unwrap();
+        if let Some(path) = &check_config.write_baseline {
+            Baseline::new(&results, &vfs, path)?.write(path)?;
+            return Ok(());
+        }
+        let baseline = check_config
+            .baseline
+            .as_ref()
+            .map(Baseline::from_path)
+            .transpose()?;
+        if let Some(baseline) = &baseline {
+            baseline.filter(&mut results, &vfs);
+        }
+        let changed_lines = check_config
+            .diff_base
+            .as_deref()
+            .map(ChangedLines::new)
+            .transpose()?;
+        if let Some(changed_lines) = &changed_lines {
+            changed_lines.filter(&mut results, &vfs);
+        }
+
+        if check_config.watch {
+            let watch = Watch::new(walkers)?;
+            let mut watched = Watched {
+                vfs,
+                results,
+                configs,
+                baseline: baseline.as_ref(),
+                diff_base: check_config.diff_base.as_deref(),
+                changed_lines,
+            };
+            loop {
+                let vfs = &watched.vfs;
+                watched
+                    .results
+                    .sort_by(|a, b| vfs.file_path(a.file_id).cmp(vfs.file_path(b.file_id)));
+                stdout
+                    .write_run(&watched.results, &watched.vfs, check_config.format)
+                    .unwrap();
+                eprintln!(
+                    "{} file(s) with findings, watching for changes",
+                    watched.results.len()
+                );
+                let changes = watch.changes()?;
+                watched.refresh_changed_lines();
+                for change in changes {
+                    watched.update(change);
+                }
+            }
+        }
+
+        stdout
+            .write_run(&results, &vfs, check_config.format)
+            .unwrap();
+
+        let outcome = Outcome::of(&results, check_config.deny, check_config.max_warnings);
+        std::process::exit(outcome as i32);
+    }
+
+    // files and results are kept across changes, only changed files are
+    // parsed and linted again
+    struct Watched<'a> {
+        vfs: ReadOnlyVfs,
+        results: Vec<LintResult>,
+        configs: Configs,
+        baseline: Option<&'a Baseline>,
+        diff_base: Option<&'a str>,
+        changed_lines: Option<ChangedLines>,
+    }
+
+    impl Watched<'_> {
+        // lines changed since a git revision shift as files are edited, a
+        // diff read from stdin cannot be read again
+        fn refresh_changed_lines(&mut self) {
+            if let Some(base) = self.diff_base.filter(|&base| base != "-")
+                && let Ok(changed) = ChangedLines::new(base)
+            {
+                self.changed_lines = Some(changed);
             }
-            std::process::exit(1);
         }
 
-        std::process::exit(0);
+        fn update(&mut self, change: Change) {
+            let (Change::Modified(path) | Change::Removed(path)) = &change;
+            if let Some(file_id) = self.vfs.file_id(path) {
+                self.results.retain(|r| r.file_id != file_id);
+                self.vfs.remove_file(file_id);
+            }
+
+            // files may be ignored by config files below the targets
+            if let Change::Modified(path) = change
+                && !self.configs.ignored(&path).unwrap_or(true)
+                && let Ok(contents) = fs::read_to_string(&path)
+            {
+                self.vfs.set_file_contents(&path, contents.as_bytes());
+                let file_id = self.vfs.alloc_file_id(&path);
+                if self.configs.assign(file_id, &path).is_err() {
+                    return;
+                }
+                let lints = self.configs.lints(file_id);
+                let session = self.configs.session(file_id);
+                let mut results = vec![lint_with(&self.vfs.entry(file_id), lints, session)];
+                if let Some(baseline) = self.baseline {
+                    baseline.filter(&mut results, &self.vfs);
+                }
+                if let Some(changed_lines) = &self.changed_lines {
+                    changed_lines.filter(&mut results, &self.vfs);
+                }
+                self.results
+                    .extend(results.into_iter().filter(|r| !r.reports.is_empty()));
+            }
+        }
     }
 }
@@ -0,0 +1,336 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    config::Configs,
+    err::LspErr,
+    lint::{LintResult, lint_with},
+};
+
+use lib::{Applicability, Diagnostic as StatixDiagnostic, Report, Severity};
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams,
+    CodeActionProviderCapability, Diagnostic, DiagnosticSeverity, DidChangeTextDocumentParams,
+    DidCloseTextDocumentParams, DidOpenTextDocumentParams, LogMessageParams, MessageType,
+    NumberOrString, Position, PublishDiagnosticsParams, Range, ServerCapabilities,
+    TextDocumentSyncCapability, TextDocumentSyncKind, TextEdit, Url, WorkspaceEdit,
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, LogMessage,
+        Notification as _, PublishDiagnostics,
+    },
+    request::{CodeActionRequest, Request as _},
+};
+use rnix::{TextRange, TextSize};
+use vfs::{FileId, ReadOnlyVfs};
+
+#[must_use]
+pub fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+        ..ServerCapabilities::default()
+    }
+}
+
+// open documents are kept in memory, keyed by their path, so that unsaved
+// changes are linted too. Each document is linted with the config file
+// that applies to its path.
+struct Server<'a> {
+    connection: &'a Connection,
+    configs: Configs,
+    vfs: ReadOnlyVfs,
+    results: HashMap<FileId, LintResult>,
+}
+
+impl<'a> Server<'a> {
+    fn new(connection: &'a Connection, configs: Configs) -> Self {
+        Self {
+            connection,
+            configs,
+            vfs: ReadOnlyVfs::default(),
+            results: HashMap::new(),
+        }
+    }
+
+    fn run(&mut self) -> Result<(), LspErr> {
+        let connection = self.connection;
+        for message in &connection.receiver {
+            match message {
+                Message::Request(request) => {
+                    if connection.handle_shutdown(&request)? {
+                        return Ok(());
+                    }
+                    self.handle_request(request)?;
+                }
+                Message::Notification(notification) => self.handle_notification(notification)?,
+                Message::Response(_) => (),
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_request(&mut self, request: Request) -> Result<(), LspErr> {
+        let id = request.id.clone();
+        let response = match request.method.as_str() {
+            CodeActionRequest::METHOD => {
+                match request.extract::<CodeActionParams>(CodeActionRequest::METHOD) {
+                    Ok((id, params)) => Response::new_ok(id, self.code_actions(&params)),
+                    Err(e) => Response::new_err(id, ErrorCode::InvalidParams as i32, e.to_string()),
+                }
+            }
+            _ => Response::new_err(
+                id,
+                ErrorCode::MethodNotFound as i32,
+                format!("unhandled method: {}", request.method),
+            ),
+        };
+        self.send(response)
+    }
+
+    fn handle_notification(&mut self, notification: Notification) -> Result<(), LspErr> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                if let Ok(params) =
+                    notification.extract::<DidOpenTextDocumentParams>(DidOpenTextDocument::METHOD)
+                {
+                    let document = params.text_document;
+                    self.update(document.uri, &document.text)?;
+                }
+            }
+            DidChangeTextDocument::METHOD => {
+                if let Ok(mut params) = notification
+                    .extract::<DidChangeTextDocumentParams>(DidChangeTextDocument::METHOD)
+                    && let Some(change) = params.content_changes.pop()
+                {
+                    self.update(params.text_document.uri, &change.text)?;
+                }
+            }
+            DidCloseTextDocument::METHOD => {
+                if let Ok(params) =
+                    notification.extract::<DidCloseTextDocumentParams>(DidCloseTextDocument::METHOD)
+                {
+                    let uri = params.text_document.uri;
+                    if let Some(file_id) = self.vfs.file_id(path_of(&uri)) {
+                        self.results.remove(&file_id);
+                    }
+                    self.publish(uri, Vec::new())?;
+                }
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
+    fn update(&mut self, uri: Url, text: &str) -> Result<(), LspErr> {
+        let path = path_of(&uri);
+        self.vfs.set_file_contents(&path, text.as_bytes());
+        let file_id = self.vfs.alloc_file_id(&path);
+        // unsaved documents may not exist on disk, they use the config file
+        // of the nearest directory that does. Documents under an invalid
+        // config file are not linted, the error is logged instead.
+        let existing = path
+            .ancestors()
+            .find(|p| p.exists())
+            .unwrap_or(Path::new("."));
+        if let Err(e) = self.configs.assign(file_id, existing) {
+            self.results.remove(&file_id);
+            self.log(MessageType::ERROR, format!("config error: {e}"))?;
+            return self.publish(uri, Vec::new());
+        }
+        let entry = self.vfs.entry(file_id);
+
+        let lints = self.configs.lints(file_id);
+        let session = self.configs.session(file_id);
+        let result = lint_with(&entry, lints, session);
+        let index = LineIndex::new(entry.contents);
+        let diagnostics = result
+            .reports
+            .iter()
+            .flat_map(|report| {
+                report
+                    .diagnostics
+                    .iter()
+                    .map(|d| to_lsp_diagnostic(report, d, &index))
+            })
+            .collect();
+
+        self.results.insert(file_id, result);
+        self.publish(uri, diagnostics)
+    }
+
+    fn code_actions(&self, params: &CodeActionParams) -> Vec<CodeActionOrCommand> {
+        let uri = &params.text_document.uri;
+        let Some(file_id) = self.vfs.file_id(path_of(uri)) else {
+            return Vec::new();
+        };
+        let Some(result) = self.results.get(&file_id) else {
+            return Vec::new();
+        };
+        let index = LineIndex::new(self.vfs.get_str(file_id));
+        let Some(requested) = index.text_range(params.range) else {
+            return Vec::new();
+        };
+
+        result
+            .reports
+            .iter()
+            .flat_map(|report| report.diagnostics.iter().map(move |d| (report, d)))
+            .filter(|(_, d)| d.at.intersect(requested).is_some())
+            .filter_map(|(report, d)| {
+                let suggestion = d.suggestion.as_ref()?;
+                let edits = suggestion
+                    .edits
+                    .iter()
+                    .map(|edit| TextEdit {
+                        range: index.range(edit.at),
+                        new_text: edit.fix.to_string(),
+                    })
+                    .collect();
+                let changes = HashMap::from([(uri.clone(), edits)]);
+                Some(CodeActionOrCommand::CodeAction(CodeAction {
+                    title: d.message.clone(),
+                    kind: Some(CodeActionKind::QUICKFIX),
+                    diagnostics: Some(vec![to_lsp_diagnostic(report, d, &index)]),
+                    is_preferred: Some(
+                        suggestion.applicability == Applicability::MachineApplicable,
+                    ),
+                    edit: Some(WorkspaceEdit {
+                        changes: Some(changes),
+                        ..WorkspaceEdit::default()
+                    }),
+                    ..CodeAction::default()
+                }))
+            })
+            .collect()
+    }
+
+    fn publish(&self, uri: Url, diagnostics: Vec<Diagnostic>) -> Result<(), LspErr> {
+        let params = PublishDiagnosticsParams {
+            uri,
+            diagnostics,
+            version: None,
+        };
+        self.send(Notification::new(PublishDiagnostics::METHOD.into(), params))
+    }
+
+    fn log(&self, typ: MessageType, message: String) -> Result<(), LspErr> {
+        let params = LogMessageParams { typ, message };
+        self.send(Notification::new(LogMessage::METHOD.into(), params))
+    }
+
+    fn send(&self, message: impl Into<Message>) -> Result<(), LspErr> {
+        self.connection
+            .sender
+            .send(message.into())
+            .map_err(|_| LspErr::Disconnected)
+    }
+}
+
+fn path_of(uri: &Url) -> PathBuf {
+    uri.to_file_path()
+        .unwrap_or_else(|()| PathBuf::from(uri.as_str()))
+}
+
+fn to_lsp_diagnostic(
+    report: &Report,
+    diagnostic: &StatixDiagnostic,
+    index: &LineIndex,
+) -> Diagnostic {
+    Diagnostic {
+        range: index.range(diagnostic.at),
+        severity: Some(match report.severity {
+            Severity::Warn => DiagnosticSeverity::WARNING,
+            Severity::Error => DiagnosticSeverity::ERROR,
+            Severity::Hint => DiagnosticSeverity::HINT,
+        }),
+        code: Some(NumberOrString::String(format!("W{:02}", report.code))),
+        source: Some("statix".into()),
+        message: diagnostic.message.clone(),
+        ..Diagnostic::default()
+    }
+}
+
+// LSP positions are (line, utf-16 code unit) pairs, statix works with
+// byte offsets
+struct LineIndex<'a> {
+    src: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    fn new(src: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(src.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self { src, line_starts }
+    }
+
+    fn position(&self, offset: TextSize) -> Position {
+        let offset = usize::from(offset);
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let character = self.src[self.line_starts[line]..offset]
+            .encode_utf16()
+            .count();
+        Position::new(
+            u32::try_from(line).unwrap_or(u32::MAX),
+            u32::try_from(character).unwrap_or(u32::MAX),
+        )
+    }
+
+    fn offset(&self, position: Position) -> Option<TextSize> {
+        let line = position.line as usize;
+        let line_start = *self.line_starts.get(line)?;
+        let line_end = self
+            .line_starts
+            .get(line + 1)
+            .map_or(self.src.len(), |&next| next - 1);
+        let mut utf16_offset = 0;
+        let byte_offset = self.src[line_start..line_end]
+            .char_indices()
+            .find_map(|(i, c)| {
+                if utf16_offset >= position.character as usize {
+                    return Some(i);
+                }
+                utf16_offset += c.len_utf16();
+                None
+            })
+            .map_or(line_end, |i| line_start + i);
+        TextSize::try_from(byte_offset).ok()
+    }
+
+    fn range(&self, at: TextRange) -> Range {
+        Range::new(self.position(at.start()), self.position(at.end()))
+    }
+
+    fn text_range(&self, range: Range) -> Option<TextRange> {
+        let start = self.offset(range.start)?;
+        let end = self.offset(range.end)?;
+        (start <= end).then(|| TextRange::new(start, end))
+    }
+}
+
+pub mod main {
+    use crate::{
+        config::{Configs, Lsp as LspConfig},
+        err::{LspErr, StatixErr},
+    };
+
+    use lsp_server::Connection;
+
+    pub fn main(lsp_config: &LspConfig) -> Result<(), StatixErr> {
+        let configs = Configs::new(lsp_config.conf_path.clone());
+
+        let (connection, io_threads) = Connection::stdio();
+        let capabilities = serde_json::to_value(super::capabilities()).unwrap();
+        connection.initialize(capabilities).map_err(LspErr::from)?;
+
+        super::Server::new(&connection, configs).run()?;
+
+        drop(connection);
+        io_threads.join().map_err(LspErr::from)?;
+        Ok(())
+    }
+}
@@ -2,10 +2,12 @@ use clap::Parser;
 
 #[rustfmt::skip]
 use statix::{
-    config::{Opts, SubCommand},
+    config::{ConfigCmd, Opts, SubCommand},
     err::StatixErr,
-    lint, fix, explain, dump, list,
+    lint, fix, explain, dump, list, config_check,
 };
+#[cfg(feature = "lsp")]
+use statix::lsp;
 
 fn main_() -> Result<(), StatixErr> {
     let opts = Opts::parse();
@@ -16,11 +18,18 @@ fn main_() -> Result<(), StatixErr> {
         SubCommand::Explain(config) => explain::main::main(&config),
         SubCommand::Dump(_) => dump::main::main(),
         SubCommand::List(_) => list::main::main(),
+        #[cfg(feature = "lsp")]
+        SubCommand::Lsp(config) => lsp::main::main(&config),
+        SubCommand::Config(config) => match config.cmd {
+            ConfigCmd::Check(config) => config_check::main::main(&config),
+        },
     }
 }
 
 fn main() {
     if let Err(e) = main_() {
         eprintln!("{e}");
+        // distinct from the exit codes of `statix check`, see `lint::Outcome`
+        std::process::exit(2);
     }
 }
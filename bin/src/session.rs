@@ -1 +1,49 @@
+use std::{env, process::Command, sync::OnceLock};
 
+use lib::session::Version;
+
+use crate::err::ConfigErr;
+
+/// Config files that do not set `nix_version` lint for the version in
+/// this variable, instead of the version of the `nix` on `PATH`
+pub const NIX_VERSION_VAR: &str = "STATIX_NIX_VERSION";
+
+/// Where the Nix version of config files without `nix_version` comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionSource {
+    /// Set in [`NIX_VERSION_VAR`]
+    Env,
+    /// Read from `nix --version`
+    Detected,
+}
+
+/// The Nix version shared by every config file that does not set
+/// `nix_version`: the one in [`NIX_VERSION_VAR`], or else the version of
+/// the `nix` on `PATH`, if any. Either is only looked up once.
+pub fn default_nix_version() -> Result<Option<(Version, VersionSource)>, ConfigErr> {
+    static DEFAULT: OnceLock<Result<Option<(Version, VersionSource)>, String>> = OnceLock::new();
+    DEFAULT
+        .get_or_init(|| match env::var(NIX_VERSION_VAR) {
+            Ok(version) => match version.parse() {
+                Ok(parsed) => Ok(Some((parsed, VersionSource::Env))),
+                Err(_) => Err(version),
+            },
+            Err(_) => Ok(detect_nix_version().map(|v| (v, VersionSource::Detected))),
+        })
+        .clone()
+        .map_err(ConfigErr::NixVersionVar)
+}
+
+// `nix --version`, if `nix` is on `PATH`
+fn detect_nix_version() -> Option<Version> {
+    let output = Command::new("nix").arg("--version").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_nix_version(&String::from_utf8_lossy(&output.stdout))
+}
+
+// `nix (Nix) 2.18.1`, the version is the last word
+fn parse_nix_version(output: &str) -> Option<Version> {
+    output.split_whitespace().last()?.parse().ok()
+}
@@ -1,16 +1,18 @@
 use std::{
     io::{self, Write},
+    path::Path,
     str,
 };
 
-use crate::{config::OutFormat, lint::LintResult};
+use crate::{config::OutFormat, fix::Fixed, lint::LintResult};
 
 use ariadne::{
     CharSet, Color, Config as CliConfig, Fmt, Label, LabelAttach, Report as CliReport,
     ReportKind as CliReportKind, Source,
 };
-use lib::Severity;
+use lib::{LINTS, Severity};
 use rnix::{TextRange, TextSize};
+use serde_json::json;
 use vfs::ReadOnlyVfs;
 
 pub trait WriteDiagnostic {
@@ -20,6 +22,15 @@ pub trait WriteDiagnostic {
         vfs: &ReadOnlyVfs,
         format: OutFormat,
     ) -> io::Result<()>;
+
+    /// Write the results of an entire run. Per-file formats write each
+    /// result in turn, whereas SARIF produces a single document.
+    fn write_run(
+        &mut self,
+        results: &[LintResult],
+        vfs: &ReadOnlyVfs,
+        format: OutFormat,
+    ) -> io::Result<()>;
 }
 
 impl<T> WriteDiagnostic for T
@@ -37,6 +48,21 @@ where
             OutFormat::Json => json::write_json(self, lint_result, vfs),
             OutFormat::StdErr => write_stderr(self, lint_result, vfs),
             OutFormat::Errfmt => write_errfmt(self, lint_result, vfs),
+            OutFormat::Sarif => sarif::write_sarif(self, std::slice::from_ref(lint_result), vfs),
+        }
+    }
+
+    fn write_run(
+        &mut self,
+        results: &[LintResult],
+        vfs: &ReadOnlyVfs,
+        format: OutFormat,
+    ) -> io::Result<()> {
+        match format {
+            OutFormat::Sarif => sarif::write_sarif(self, results, vfs),
+            _ => results
+                .iter()
+                .try_for_each(|result| WriteDiagnostic::write(self, result, vfs, format)),
         }
     }
 }
@@ -128,7 +154,7 @@ mod json {
 
     use std::io::{self, Write};
 
-    use lib::Severity;
+    use lib::{Applicability, Severity};
     use rnix::TextRange;
     use serde::Serialize;
     use vfs::ReadOnlyVfs;
@@ -157,6 +183,17 @@ mod json {
 
     #[derive(Serialize)]
     struct JsonSuggestion {
+        at: JsonSpan,
+        // the replacement of suggestions made of a single edit, as printed
+        // before suggestions could hold several edits
+        #[serde(skip_serializing_if = "Option::is_none")]
+        fix: Option<String>,
+        edits: Vec<JsonEdit>,
+        applicability: Applicability,
+    }
+
+    #[derive(Serialize)]
+    struct JsonEdit {
         at: JsonSpan,
         fix: String,
     }
@@ -211,8 +248,20 @@ mod json {
                         at: JsonSpan::from_textrange(d.at, src),
                         message: &d.message,
                         suggestion: d.suggestion.as_ref().map(|s| JsonSuggestion {
-                            at: JsonSpan::from_textrange(s.at, src),
-                            fix: s.fix.to_string(),
+                            at: JsonSpan::from_textrange(s.at(), src),
+                            fix: match s.edits.as_slice() {
+                                [edit] => Some(edit.fix.to_string()),
+                                _ => None,
+                            },
+                            edits: s
+                                .edits
+                                .iter()
+                                .map(|e| JsonEdit {
+                                    at: JsonSpan::from_textrange(e.at, src),
+                                    fix: e.fix.to_string(),
+                                })
+                                .collect(),
+                            applicability: s.applicability,
                         }),
                     })
                     .collect::<Vec<_>>();
@@ -233,6 +282,201 @@ mod json {
     }
 }
 
+mod sarif {
+    use crate::lint::LintResult;
+
+    use std::{
+        io::{self, Write},
+        path::Path,
+    };
+
+    use lib::{Diagnostic, LINTS, Report, Severity};
+    use rnix::{TextRange, TextSize};
+    use serde_json::{Value, json};
+    use url::Url;
+    use vfs::ReadOnlyVfs;
+
+    fn rule_id(code: u32) -> String {
+        format!("W{code:02}")
+    }
+
+    fn rules(results: &[LintResult]) -> Vec<Value> {
+        let syntax_error = json!({
+            "id": rule_id(0),
+            "name": "syntax_error",
+            "shortDescription": { "text": "Syntax error" },
+            "defaultConfiguration": { "level": level(Severity::Error) },
+        });
+        std::iter::once(syntax_error)
+            .chain(LINTS.iter().map(|lint| {
+                // `[severity]` overrides are followed as long as the lint is
+                // reported with one severity throughout the run
+                let mut severities = results
+                    .iter()
+                    .flat_map(|r| &r.reports)
+                    .filter(|r| r.code == lint.code())
+                    .map(|r| r.severity);
+                let severity = match severities.next() {
+                    Some(first) if severities.all(|s| s == first) => first,
+                    _ => lint.report().severity,
+                };
+                json!({
+                    "id": rule_id(lint.code()),
+                    "name": lint.name(),
+                    "shortDescription": { "text": lint.note() },
+                    "fullDescription": { "text": lint.explanation() },
+                    "help": { "text": lint.explanation(), "markdown": lint.explanation() },
+                    "defaultConfiguration": { "level": level(severity) },
+                })
+            }))
+            .collect()
+    }
+
+    fn level(severity: Severity) -> &'static str {
+        match severity {
+            Severity::Warn => "warning",
+            Severity::Error => "error",
+            Severity::Hint => "note",
+        }
+    }
+
+    fn uri(path: &Path) -> String {
+        if path.is_absolute()
+            && let Ok(url) = Url::from_file_path(path)
+        {
+            return url.to_string();
+        }
+        path.to_string_lossy().replace('\\', "/")
+    }
+
+    // sarif lines and columns are 1-indexed, columns are counted in
+    // unicode code points, see `columnKind` below
+    fn position(at: TextSize, src: &str) -> (usize, usize) {
+        let at = usize::from(at);
+        let line_start = src[..at].rfind('\n').map_or(0, |i| i + 1);
+        (
+            super::line(at.try_into().unwrap(), src),
+            src[line_start..at].chars().count() + 1,
+        )
+    }
+
+    fn region(at: TextRange, src: &str) -> Value {
+        let (start_line, start_column) = position(at.start(), src);
+        let (end_line, end_column) = position(at.end(), src);
+        json!({
+            "startLine": start_line,
+            "startColumn": start_column,
+            "endLine": end_line,
+            "endColumn": end_column,
+        })
+    }
+
+    fn result(report: &Report, diagnostic: &Diagnostic, uri: &str, src: &str) -> Value {
+        let location = json!({
+            "artifactLocation": { "uri": uri },
+            "region": region(diagnostic.at, src),
+        });
+        let mut result = json!({
+            "ruleId": rule_id(report.code),
+            "level": level(report.severity),
+            "message": { "text": diagnostic.message },
+            "locations": [{ "physicalLocation": location }],
+        });
+        if let Some(suggestion) = &diagnostic.suggestion {
+            let replacements = suggestion
+                .edits
+                .iter()
+                .map(|edit| {
+                    json!({
+                        "deletedRegion": region(edit.at, src),
+                        "insertedContent": { "text": edit.fix.to_string() },
+                    })
+                })
+                .collect::<Vec<_>>();
+            result["fixes"] = json!([{
+                "description": { "text": diagnostic.message },
+                "artifactChanges": [{
+                    "artifactLocation": { "uri": uri },
+                    "replacements": replacements,
+                }],
+                "properties": { "applicability": suggestion.applicability.to_string() },
+            }]);
+        }
+        result
+    }
+
+    pub fn write_sarif<T: Write>(
+        writer: &mut T,
+        results: &[LintResult],
+        vfs: &ReadOnlyVfs,
+    ) -> io::Result<()> {
+        let rules = rules(results);
+        let results = results
+            .iter()
+            .flat_map(|lint_result| {
+                let file_id = lint_result.file_id;
+                let uri = uri(vfs.file_path(file_id));
+                let src = vfs.get_str(file_id);
+                lint_result
+                    .reports
+                    .iter()
+                    .flat_map(|report| report.diagnostics.iter().map(move |d| (report, d)))
+                    .map(move |(report, d)| result(report, d, &uri, src))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let sarif = json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "statix",
+                        "informationUri": "https://github.com/oppiliappan/statix",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "rules": rules,
+                    }
+                },
+                "columnKind": "unicodeCodePoints",
+                "results": results,
+            }],
+        });
+        writeln!(writer, "{}", serde_json::to_string_pretty(&sarif).unwrap())
+    }
+}
+
+/// Write the fixes made to a file as JSON, `src` is the file as it was
+/// before it was fixed
+pub fn write_fixes<T: Write>(
+    writer: &mut T,
+    path: &Path,
+    src: &str,
+    fixed: &[Fixed],
+) -> io::Result<()> {
+    let edits = fixed
+        .iter()
+        .map(|f| {
+            let name = LINTS
+                .iter()
+                .find(|l| l.code() == f.code)
+                .map_or("unknown", |l| l.name());
+            json!({
+                "code": f.code,
+                "name": name,
+                "range": { "start": usize::from(f.at.start()), "end": usize::from(f.at.end()) },
+                "at": {
+                    "from": { "line": line(f.at.start(), src), "column": column(f.at.start(), src) },
+                    "to": { "line": line(f.at.end(), src), "column": column(f.at.end(), src) },
+                },
+                "fix": f.fix,
+            })
+        })
+        .collect::<Vec<_>>();
+    let out = json!({ "file": path, "edits": edits });
+    writeln!(writer, "{}", serde_json::to_string_pretty(&out).unwrap())
+}
+
 fn line(at: TextSize, src: &str) -> usize {
     let at = at.into();
     src[..at].chars().filter(|&c| c == '\n').count() + 1
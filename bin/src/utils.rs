@@ -1,26 +1,127 @@
 use std::collections::HashMap;
 
-use lib::{LINTS, Lint};
-use rnix::SyntaxKind;
+use lib::{Context, Explain, LINTS, Lint, Metadata, Report, Rule, Severity};
+use rnix::{SyntaxElement, SyntaxKind};
 
-#[allow(clippy::borrowed_box)]
-pub fn lint_map_of(
-    lints: &[&'static Box<dyn Lint>],
-) -> HashMap<SyntaxKind, Vec<&'static Box<dyn Lint>>> {
+use crate::LintMap;
+
+/// A lint along with settings from the config file. Reports produced by
+/// the lint carry the configured severity, if any.
+#[derive(Clone, Copy)]
+pub struct ConfiguredLint {
+    lint: &'static dyn Lint,
+    severity: Option<Severity>,
+}
+
+impl ConfiguredLint {
+    #[must_use]
+    pub fn new(lint: &'static dyn Lint) -> Self {
+        Self {
+            lint,
+            severity: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_severity(self, severity: Option<Severity>) -> Self {
+        Self { severity, ..self }
+    }
+
+    fn configure(&self, report: Report) -> Report {
+        match self.severity {
+            Some(severity) => report.severity(severity),
+            None => report,
+        }
+    }
+}
+
+impl Metadata for ConfiguredLint {
+    fn name(&self) -> &'static str {
+        self.lint.name()
+    }
+    fn note(&self) -> &'static str {
+        self.lint.note()
+    }
+    fn code(&self) -> u32 {
+        self.lint.code()
+    }
+    fn report(&self) -> Report {
+        self.configure(self.lint.report())
+    }
+    fn match_with(&self, with: &SyntaxKind) -> bool {
+        self.lint.match_with(with)
+    }
+    fn match_kind(&self) -> Vec<SyntaxKind> {
+        self.lint.match_kind()
+    }
+    fn options(&self) -> &'static [lib::options::LintOption] {
+        self.lint.options()
+    }
+}
+
+impl Explain for ConfiguredLint {
+    fn explanation(&self) -> &'static str {
+        self.lint.explanation()
+    }
+}
+
+impl Rule for ConfiguredLint {
+    fn validate(&self, node: &SyntaxElement, ctx: &Context) -> Option<Report> {
+        self.lint
+            .validate(node, ctx)
+            .map(|report| self.configure(report))
+    }
+}
+
+#[must_use]
+pub fn lint_map_of(lints: &[ConfiguredLint]) -> LintMap {
     let mut map = HashMap::new();
     for lint in lints {
-        let lint = *lint;
         let matches = lint.match_kind();
         for m in matches {
             map.entry(m)
-                .and_modify(|v: &mut Vec<_>| v.push(lint))
-                .or_insert_with(|| vec![lint]);
+                .and_modify(|v: &mut Vec<_>| v.push(*lint))
+                .or_insert_with(|| vec![*lint]);
         }
     }
     map
 }
 
-#[allow(clippy::borrowed_box)]
-pub fn lint_map() -> HashMap<SyntaxKind, Vec<&'static Box<dyn Lint>>> {
-    lint_map_of(&LINTS)
+#[must_use]
+pub fn lint_map() -> LintMap {
+    lint_map_of(
+        (*LINTS)
+            .iter()
+            .map(|&l| ConfiguredLint::new(l.as_ref()))
+            .collect::<Vec<_>>()
+            .as_slice(),
+    )
+}
+
+/// The candidate closest to `name`, if it is close enough for `name` to be
+/// a likely typo of it
+pub fn closest<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let max_distance = (name.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+// levenshtein distance, in chars
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitute = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitute.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
 }
@@ -0,0 +1,116 @@
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver},
+    time::Duration,
+};
+
+use crate::{
+    dirs::{Walker, is_nix},
+    err::WatchErr,
+};
+
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher as _};
+
+// editors tend to write files in several steps, events that arrive within
+// this window of each other are handled together
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// A change to a `.nix` file under the watched target
+#[derive(Debug, PartialEq, Eq)]
+pub enum Change {
+    Modified(PathBuf),
+    Removed(PathBuf),
+}
+
+pub struct Watch {
+    _watcher: RecommendedWatcher,
+    events: Receiver<notify::Result<Event>>,
+    roots: Vec<Root>,
+}
+
+// a watched target, along with its canonical path, which events are
+// reported with
+struct Root {
+    canonical_target: PathBuf,
+    walker: Walker,
+}
+
+impl Root {
+    fn relative(&self, path: &Path) -> Option<PathBuf> {
+        let target = self.walker.target();
+        if self.canonical_target.is_dir() {
+            let rest = path.strip_prefix(&self.canonical_target).ok()?;
+            Some(target.join(rest))
+        } else {
+            (path == self.canonical_target).then(|| target.to_path_buf())
+        }
+    }
+}
+
+impl Watch {
+    pub fn new(walkers: Vec<Walker>) -> Result<Self, WatchErr> {
+        let (tx, events) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(tx)?;
+        let mut roots = Vec::new();
+        for walker in walkers {
+            let canonical_target = fs::canonicalize(walker.target())?;
+            if canonical_target.is_dir() {
+                watcher.watch(&canonical_target, RecursiveMode::Recursive)?;
+            } else {
+                // files are often replaced rather than written to, watching
+                // the file itself would miss those changes
+                let parent = canonical_target.parent().unwrap_or(&canonical_target);
+                watcher.watch(parent, RecursiveMode::NonRecursive)?;
+            }
+            roots.push(Root {
+                canonical_target,
+                walker,
+            });
+        }
+
+        Ok(Self {
+            _watcher: watcher,
+            events,
+            roots,
+        })
+    }
+
+    /// Block until `.nix` files that are not ignored change. Paths are
+    /// formed in the same way as those produced by `Walker::files`, so
+    /// that they map to the same `FileId`.
+    pub fn changes(&self) -> Result<Vec<Change>, WatchErr> {
+        loop {
+            let mut paths = BTreeSet::new();
+            let event = self.events.recv().map_err(|_| WatchErr::Disconnected)?;
+            paths.extend(event?.paths);
+            while let Ok(event) = self.events.recv_timeout(DEBOUNCE) {
+                paths.extend(event?.paths);
+            }
+
+            let changes = self
+                .roots
+                .iter()
+                .flat_map(|root| {
+                    paths.iter().filter_map(|path| {
+                        let path = root.relative(path)?;
+                        if path.is_file() {
+                            root.walker
+                                .includes(&path)
+                                .then_some(Change::Modified(path))
+                        } else {
+                            // ignore rules cannot be checked against files
+                            // that no longer exist, removing an unknown file
+                            // is a no-op
+                            is_nix(&path).then_some(Change::Removed(path))
+                        }
+                    })
+                })
+                .collect::<Vec<_>>();
+            if !changes.is_empty() {
+                return Ok(changes);
+            }
+        }
+    }
+}
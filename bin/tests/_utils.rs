@@ -1,18 +1,57 @@
-use std::{io::Write, process::Command};
+// each test crate uses only some of these helpers
+#![allow(dead_code)]
 
-use tempfile::NamedTempFile;
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use tempfile::{NamedTempFile, TempDir};
+
+/// The `statix` binary built for these tests, linting for the same Nix
+/// version whichever `nix` is installed
+#[must_use]
+pub fn statix() -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_statix"));
+    command.env("STATIX_NIX_VERSION", NIX_VERSION);
+    command
+}
+
+/// The Nix version [`statix`] lints for, unless a config file sets one
+pub const NIX_VERSION: &str = "2.18";
 
 pub fn test_cli(expression: &str, args: &[&str]) -> anyhow::Result<String> {
-    let mut fixture = NamedTempFile::with_suffix(".nix")?;
+    let fixture = NamedTempFile::with_suffix(".nix")?;
+    run_fixture(fixture, expression, statix().args(args))
+}
+
+/// Like [`test_cli`], with `config` as the `statix.toml` in effect
+pub fn test_cli_with_config(
+    expression: &str,
+    config: &str,
+    args: &[&str],
+) -> anyhow::Result<String> {
+    let dir = TempDir::new()?;
+    fs::write(dir.path().join("statix.toml"), config)?;
+    let fixture = NamedTempFile::with_suffix_in(".nix", dir.path())?;
+    run_fixture(
+        fixture,
+        expression,
+        statix().args(args).arg("-c").arg(dir.path()),
+    )
+}
+
+fn run_fixture(
+    mut fixture: NamedTempFile,
+    expression: &str,
+    command: &mut Command,
+) -> anyhow::Result<String> {
     fixture.write_all(expression.as_bytes())?;
     fixture.write_all(b"\n")?; // otherwise diff says there's no newline at end of file
 
-    let output = Command::new("cargo")
-        .arg("run")
-        .arg("--")
-        .args(args)
-        .arg(fixture.path())
-        .output()?;
+    let output = command.arg(fixture.path()).output()?;
 
     let stdout = strip_ansi_escapes::strip(output.stdout)?;
     let stdout = String::from_utf8(stdout)?;
@@ -20,3 +59,91 @@ pub fn test_cli(expression: &str, args: &[&str]) -> anyhow::Result<String> {
 
     Ok(stdout)
 }
+
+/// A temporary directory of files that `statix` is run in
+pub struct Tree(TempDir);
+
+/// The output of a `statix` run, with the path of the [`Tree`] replaced by
+/// `<temp_dir>`
+pub struct Output {
+    pub stdout: String,
+    pub stderr: String,
+    pub code: Option<i32>,
+}
+
+impl Tree {
+    /// A directory holding `files`, as pairs of paths relative to it and
+    /// contents
+    #[must_use]
+    pub fn new(files: &[(&str, &str)]) -> Self {
+        let tree = Self(TempDir::new().unwrap());
+        for (path, contents) in files {
+            tree.write(path, contents);
+        }
+        tree
+    }
+
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        self.0.path()
+    }
+
+    #[must_use]
+    pub fn join(&self, path: &str) -> PathBuf {
+        self.path().join(path)
+    }
+
+    pub fn write(&self, path: &str, contents: &str) {
+        let path = self.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    #[must_use]
+    pub fn read(&self, path: &str) -> String {
+        fs::read_to_string(self.join(path)).unwrap()
+    }
+
+    /// `statix` with `args`, run from within the directory
+    #[must_use]
+    pub fn command(&self, args: &[&str]) -> Command {
+        let mut command = statix();
+        command.args(args).current_dir(self.path());
+        command
+    }
+
+    /// Runs `statix` with `args` from within the directory
+    #[must_use]
+    pub fn run(&self, args: &[&str]) -> Output {
+        self.output(&mut self.command(args), "")
+    }
+
+    /// Runs `command` to completion with `stdin` as its input
+    pub fn output(&self, command: &mut Command, stdin: &str) -> Output {
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(stdin.as_bytes())
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+
+        let clean = |bytes: Vec<u8>| {
+            let bytes = strip_ansi_escapes::strip(bytes).unwrap();
+            String::from_utf8(bytes)
+                .unwrap()
+                .replace(self.path().to_str().unwrap(), "<temp_dir>")
+        };
+        Output {
+            stdout: clean(output.stdout),
+            stderr: clean(output.stderr),
+            code: output.status.code(),
+        }
+    }
+}
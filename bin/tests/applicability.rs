@@ -0,0 +1,24 @@
+mod _utils;
+
+use _utils::Tree;
+
+// `empty_let_in` is machine applicable, `eta_reduction` is not, removing
+// the unused argument of `c` leaves an empty pattern, which is cleaned up
+// in turn
+fn fix(args: &[&str]) -> String {
+    let src = "{ a = let in 1; b = x: f x; c = { d, ... }: null; }\n";
+    let tree = Tree::new(&[("default.nix", src)]);
+    let output = tree.run(&[&["fix"], args, &["default.nix"]].concat());
+    assert_eq!(output.code, Some(0));
+    tree.read("default.nix")
+}
+
+#[test]
+fn only_machine_applicable_by_default() {
+    assert_eq!(fix(&[]), "{ a = 1; b = x: f x; c = _: null; }\n");
+}
+
+#[test]
+fn unsafe_fixes() {
+    assert_eq!(fix(&["--unsafe-fixes"]), "{ a = 1; b = f; c = _: null; }\n");
+}
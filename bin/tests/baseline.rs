@@ -0,0 +1,75 @@
+mod _utils;
+
+use indoc::indoc;
+
+use _utils::Tree;
+
+fn check(tree: &Tree, args: &[&str]) -> String {
+    let file = tree.join("default.nix");
+    let args = [&["check", "-o", "errfmt"], args, &[file.to_str().unwrap()]].concat();
+    tree.run(&args).stdout
+}
+
+#[test]
+fn reports_only_new_findings() {
+    let tree = Tree::new(&[]);
+    let baseline = tree.join("statix-baseline.json");
+    let baseline = baseline.to_str().unwrap();
+
+    tree.write(
+        "default.nix",
+        indoc! {"
+            let
+              a = 1;
+            in
+            { a = a; b = { a = a; }; }
+        "},
+    );
+    assert!(check(&tree, &["--write-baseline", baseline]).is_empty());
+    assert!(check(&tree, &["--baseline", baseline]).is_empty());
+
+    // shift existing findings down, reformat them and add a new one
+    tree.write(
+        "default.nix",
+        indoc! {"
+            # a comment
+
+            let
+              a = 1;
+            in
+            { a  =  a; b = { a = a; }; c = { a = a; }; }
+        "},
+    );
+    insta::assert_snapshot!(check(&tree, &["--baseline", baseline]));
+}
+
+#[test]
+fn paths_are_relative_to_the_baseline() {
+    let tree = Tree::new(&[("pkgs/default.nix", "{ a = a; }")]);
+    let file = tree.join("pkgs/default.nix");
+    let baseline = tree.join("statix-baseline.json");
+    let output = tree.run(&[
+        "check",
+        "--write-baseline",
+        baseline.to_str().unwrap(),
+        file.to_str().unwrap(),
+    ]);
+    assert_eq!(output.code, Some(0));
+    assert!(
+        tree.read("statix-baseline.json")
+            .contains("\"pkgs/default.nix\"")
+    );
+
+    // the same file, named differently and checked from elsewhere
+    let mut command = tree.command(&[
+        "check",
+        "-o",
+        "errfmt",
+        "--baseline",
+        "../statix-baseline.json",
+        ".",
+    ]);
+    let output = tree.output(command.current_dir(tree.join("pkgs")), "");
+    assert_eq!(output.stdout, "");
+    assert_eq!(output.code, Some(0));
+}
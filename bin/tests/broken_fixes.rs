@@ -0,0 +1,22 @@
+mod _utils;
+
+use _utils::Tree;
+
+// `!(a == b) == c` simplifies to `a != b == c`, which does not parse, as
+// comparisons do not associate
+#[test]
+fn broken_fixes_are_reverted() {
+    let tree = Tree::new(&[("default.nix", "{ a = !(a == b) == c; b = !(x == y); }\n")]);
+    let output = tree.run(&["fix", "default.nix"]);
+    assert_eq!(output.code, Some(0));
+
+    let stderr = output.stderr;
+    assert!(
+        stderr.contains("fix for `bool_simplification` (W18) introduces a syntax error"),
+        "{stderr}"
+    );
+    assert_eq!(
+        tree.read("default.nix"),
+        "{ a = !(a == b) == c; b = x != y; }\n"
+    );
+}
@@ -0,0 +1,35 @@
+#![cfg(feature = "json")]
+
+mod _utils;
+
+use serde_json::Value;
+
+fn suggestions(expression: &str) -> Vec<Value> {
+    let stdout = _utils::test_cli(expression, &["check", "-o", "json"]).unwrap();
+    let json: Value = serde_json::from_str(&stdout).unwrap();
+    json["report"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .flat_map(|report| report["diagnostics"].as_array().unwrap())
+        .filter_map(|diagnostic| diagnostic.get("suggestion").cloned())
+        .filter(|suggestion| !suggestion.is_null())
+        .collect()
+}
+
+// `fix` is kept for suggestions made of a single edit
+#[test]
+fn single_edit() {
+    let suggestions = suggestions("let a = 1; in { a = a; }");
+    assert_eq!(suggestions.len(), 1);
+    assert_eq!(suggestions[0]["fix"], "inherit a;");
+    assert_eq!(suggestions[0]["edits"][0]["fix"], "inherit a;");
+}
+
+#[test]
+fn several_edits() {
+    let suggestions = suggestions("let inherit (x) a b c; in b");
+    assert_eq!(suggestions.len(), 1);
+    assert!(suggestions[0].get("fix").is_none());
+    assert_eq!(suggestions[0]["edits"].as_array().unwrap().len(), 2);
+}
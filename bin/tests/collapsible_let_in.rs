@@ -17,6 +17,20 @@ generate_tests! {
                 d = 6;
               in
               a + b + c + d
-        "}
+        "},
+        indoc! {r"
+            {
+              x =
+                let
+                  a = 2;
+                in
+                # b is used in the body
+                let
+                  b = 3;
+                in
+                a + b;
+            }
+        "},
+        "let a = 2; in let b = 3; in a + b",
     ],
 }
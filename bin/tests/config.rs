@@ -0,0 +1,243 @@
+mod _utils;
+
+use indoc::indoc;
+
+use _utils::{NIX_VERSION, Output, Tree, statix};
+
+// runs `statix check` on `expression` with `config` as the statix.toml
+fn check_with_config(config: &str, expression: &str) -> String {
+    let tree = Tree::new(&[("statix.toml", config), ("default.nix", expression)]);
+    let dir = tree.path().to_str().unwrap();
+    tree.run(&["check", "-o", "errfmt", "-c", dir, dir]).stdout
+}
+
+#[test]
+fn severity_overrides() {
+    let stdout = check_with_config(
+        indoc! {r#"
+            [severity]
+            manual_inherit = "error"
+            useless_parens = "hint"
+        "#},
+        "let a = a; b = (1); in { inherit a b; c = null == null; }",
+    );
+    insta::assert_snapshot!(stdout);
+}
+
+#[test]
+fn old_nix_version() {
+    let stdout = check_with_config(
+        r#"nix_version = "1.11""#,
+        "{ a = builtins.toPath x; b = c.${d}; }",
+    );
+    insta::assert_snapshot!(stdout);
+}
+
+#[test]
+fn new_nix_version() {
+    let stdout = check_with_config(r#"nix_version = "2.18.1""#, "{ b = c.${d}; }");
+    assert_eq!(stdout, "");
+}
+
+// runs `statix check` with `config` as the statix.toml, expecting it to
+// fail, returns stderr
+fn check_invalid_config(config: &str) -> String {
+    let tree = Tree::new(&[("statix.toml", config), ("default.nix", "null")]);
+    let dir = tree.path().to_str().unwrap();
+    let output = tree.run(&["check", "-c", dir, dir]);
+    assert_eq!(output.code, Some(2));
+    output.stderr
+}
+
+#[test]
+fn invalid_nix_version() {
+    let stderr = check_invalid_config(r#"nix_version = "two""#);
+    assert!(stderr.contains("unable to parse nix version: `two`"));
+}
+
+// runs `statix check` on `expression` with `STATIX_NIX_VERSION` set to
+// `version`, and `config` as the statix.toml
+fn check_with_nix_version(version: &str, config: &str, expression: &str) -> Output {
+    let tree = Tree::new(&[("statix.toml", config), ("default.nix", expression)]);
+    let dir = tree.path().to_str().unwrap();
+    let mut command = tree.command(&["check", "-o", "errfmt", "-c", dir, dir]);
+    tree.output(command.env("STATIX_NIX_VERSION", version), "")
+}
+
+#[test]
+fn nix_version_from_env() {
+    let output = check_with_nix_version("2.3", "", "{ b = c.${d}; }");
+    assert!(output.stdout.contains(":W:9:"), "{}", output.stdout);
+
+    // the config file wins over the environment
+    let output = check_with_nix_version("2.3", r#"nix_version = "2.18""#, "{ b = c.${d}; }");
+    assert_eq!(output.stdout, "");
+
+    let output = check_with_nix_version("two", "", "null");
+    assert_eq!(output.code, Some(2));
+    assert!(
+        output
+            .stderr
+            .contains("unable to parse nix version in STATIX_NIX_VERSION: `two`"),
+        "{}",
+        output.stderr
+    );
+}
+
+#[test]
+fn lint_options() {
+    let stdout = check_with_config(
+        indoc! {r"
+            [lints.repeated_keys]
+            threshold = 2
+
+            [lints.useless_parens]
+            let_body = false
+
+            [lints.bool_simplification]
+            negated_equality = false
+            negated_inequality = true
+        "},
+        "let a = (1); in ({ b.c = a; b.d = !(a == a); e = !(a != a); })",
+    );
+    insta::assert_snapshot!(stdout);
+}
+
+#[test]
+fn invalid_lint_options() {
+    let stderr = check_invalid_config("[lints.repeated_key]\nthreshold = 2");
+    assert!(stderr.contains("no lint named `repeated_key`"));
+
+    let stderr = check_invalid_config("[lints.repeated_keys]\nlimit = 2");
+    assert!(stderr.contains("`repeated_keys` has no option `limit`"));
+
+    let stderr = check_invalid_config("[lints.repeated_keys]\nthreshold = true");
+    assert!(stderr.contains("`repeated_keys.threshold` should be a non-negative integer"));
+
+    let stderr = check_invalid_config("[lints.repeated_keys]\nthreshold = 1");
+    assert!(stderr.contains("`repeated_keys.threshold` should be at least 2, found `1`"));
+
+    let stderr = check_invalid_config("[lints.useless_parens]\nbindings = 1");
+    assert!(stderr.contains("`useless_parens.bindings` should be a boolean"));
+}
+
+#[test]
+fn dump_round_trips() {
+    let output = statix().arg("dump").output().unwrap();
+    let dumped = String::from_utf8(output.stdout).unwrap();
+    assert!(dumped.contains("[lints.repeated_keys]\nthreshold = 3\n"));
+
+    let stdout = check_with_config(&dumped, "{ a.b = 1; a.c = 2; a.d = 3; }");
+    assert!(stdout.contains("W:20:"));
+}
+
+// runs `statix check` on a directory holding `files`, config files are
+// discovered from each checked file
+fn check_tree(files: &[(&str, &str)]) -> String {
+    let tree = Tree::new(files);
+    tree.run(&["check", "-o", "errfmt", tree.path().to_str().unwrap()])
+        .stdout
+}
+
+// raises `manual_inherit` and `useless_parens`
+const BOTH: &str = "let a = 1; in { a = a; b = (a); }";
+
+#[test]
+fn nested_configs() {
+    let stdout = check_tree(&[
+        (
+            "statix.toml",
+            indoc! {r#"
+                disabled = ["manual_inherit"]
+
+                [[overrides]]
+                files = ["hosts/**"]
+                disabled = ["useless_parens"]
+            "#},
+        ),
+        ("default.nix", BOTH),
+        ("hosts/a.nix", BOTH),
+        (
+            "pkgs/statix.toml",
+            indoc! {r#"
+                enabled = ["manual_inherit"]
+                ignore = ["vendored.nix"]
+
+                [severity]
+                useless_parens = "error"
+            "#},
+        ),
+        ("pkgs/b.nix", BOTH),
+        ("pkgs/vendored.nix", BOTH),
+        ("lib/statix.toml", "root = true"),
+        ("lib/c.nix", BOTH),
+    ]);
+    insta::assert_snapshot!(stdout);
+}
+
+#[test]
+fn unknown_names() {
+    let stderr = check_invalid_config(r#"disable = ["manual_inherit"]"#);
+    assert!(stderr.contains("unknown field `disable`, did you mean `disabled`?"));
+
+    let stderr = check_invalid_config(r#"disabled = ["manual_inherti"]"#);
+    assert!(stderr.contains("no lint named `manual_inherti`, did you mean `manual_inherit`?"));
+
+    let stderr = check_invalid_config("[severity]\nuseles_parens = \"hint\"");
+    assert!(stderr.contains("did you mean `useless_parens`?"));
+
+    let stderr = check_invalid_config("[[overrides]]\nfiles = [\"a\"]\nenable = []");
+    assert!(stderr.contains("[[overrides]] #1: unknown field `enable`, did you mean `enabled`?"));
+
+    // fields that are not close to any are reported as they are
+    let stderr = check_invalid_config("colour = true");
+    assert!(stderr.contains("unknown field `colour`"), "{stderr}");
+
+    let stderr = check_invalid_config("[lints.repeated_keys]\nthreshhold = 2");
+    assert!(stderr.contains("did you mean `threshold`?"));
+}
+
+#[test]
+fn config_check() {
+    let tree = Tree::new(&[
+        (
+            "statix.toml",
+            indoc! {r#"
+                disabled = ["manual_inherit", "useless_rec"]
+                nix_version = "2.3"
+
+                [lints.repeated_keys]
+                threshold = 2
+
+                [[overrides]]
+                files = ["pkgs/**"]
+                severity = { useless_parens = "error" }
+                lints = { repeated_keys = { threshold = 4 } }
+            "#},
+        ),
+        (
+            "pkgs/statix.toml",
+            indoc! {r#"
+                enabled = ["manual_inherit"]
+                ignore = ["vendored.nix"]
+            "#},
+        ),
+        ("pkgs/a.nix", "null"),
+    ]);
+    let target = tree.join("pkgs/a.nix");
+    let output = tree.run(&["config", "check", target.to_str().unwrap()]);
+    insta::assert_snapshot!(output.stdout);
+}
+
+#[test]
+fn config_check_nix_version_provenance() {
+    let tree = Tree::new(&[("default.nix", "null")]);
+    let output = tree.run(&["config", "check", "default.nix"]);
+    assert!(
+        output.stdout.contains(&format!(
+            "# nix_version is not set, {NIX_VERSION}.0 is configured with STATIX_NIX_VERSION"
+        )),
+        "{}",
+        output.stdout
+    );
+}
@@ -0,0 +1,110 @@
+mod _utils;
+
+use std::{
+    path::Path,
+    process::{Command, Stdio},
+};
+
+use indoc::indoc;
+
+use _utils::Tree;
+
+fn statix(tree: &Tree, args: &[&str], stdin: &str) -> String {
+    tree.output(&mut tree.command(args), stdin).stdout
+}
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .args([
+            "-c",
+            "user.name=statix",
+            "-c",
+            "user.email=statix@example.com",
+        ])
+        .args(args)
+        .current_dir(dir)
+        .stdout(Stdio::null())
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
+// a legacy finding on line 2 is committed, a new one is added on line 3
+fn setup() -> Tree {
+    let tree = Tree::new(&[("default.nix", "{\n  a = let in 1;\n}\n")]);
+    git(tree.path(), &["init", "-q"]);
+    git(tree.path(), &["add", "."]);
+    git(tree.path(), &["commit", "-q", "-m", "init"]);
+    tree.write("default.nix", "{\n  a = let in 1;\n  b = let in 2;\n}\n");
+    tree
+}
+
+#[test]
+fn check_against_git_revision() {
+    let tree = setup();
+    let out = statix(&tree, &["check", "-o", "errfmt", "--diff-base", "HEAD"], "");
+    assert_eq!(out.lines().count(), 1, "{out}");
+    assert!(out.contains(">3:7:W:2:"), "{out}");
+}
+
+#[test]
+fn check_against_diff_on_stdin() {
+    let tree = setup();
+    // as if only line 2 had changed
+    let diff = indoc! {"
+        --- a/default.nix
+        +++ b/default.nix
+        @@ -2 +2 @@
+        -  a = 1;
+        +  a = let in 1;
+    "};
+    let out = statix(&tree, &["check", "-o", "errfmt", "--diff-base", "-"], diff);
+    assert_eq!(out.lines().count(), 1, "{out}");
+    assert!(out.contains(">2:7:W:2:"), "{out}");
+}
+
+#[test]
+fn fix_against_git_revision() {
+    let tree = setup();
+    statix(&tree, &["fix", "--diff-base", "HEAD"], "");
+    assert_eq!(
+        tree.read("default.nix"),
+        "{\n  a = let in 1;\n  b = 2;\n}\n"
+    );
+}
+
+#[test]
+fn untracked_files_are_changed() {
+    let tree = setup();
+    tree.write("new.nix", "let in null\n");
+    let out = statix(
+        &tree,
+        &["check", "-o", "errfmt", "--diff-base", "HEAD", "new.nix"],
+        "",
+    );
+    assert!(out.contains("new.nix>1:1:W:2:"), "{out}");
+}
+
+#[test]
+fn stdin_is_read_once() {
+    let tree = setup();
+    let output = tree.output(
+        &mut tree.command(&["check", "--diff-base", "-", "--files-from", "-"]),
+        "",
+    );
+    assert_eq!(output.code, Some(2));
+    assert!(output.stderr.contains("cannot both read from stdin"));
+}
+
+#[test]
+fn fix_reports_crossing_a_hunk_boundary() {
+    let tree = setup();
+    tree.write("default.nix", "{\n  a = let\n  in 1;\n}\n");
+    git(tree.path(), &["commit", "-q", "-am", "split"]);
+    // only the second line of the let-in changes
+    tree.write("default.nix", "{\n  a = let\n  in 2;\n}\n");
+    let out = statix(&tree, &["check", "-o", "errfmt", "--diff-base", "HEAD"], "");
+    assert!(out.contains(">2:7:W:2:"), "{out}");
+    statix(&tree, &["fix", "--diff-base", "HEAD"], "");
+    assert_eq!(tree.read("default.nix"), "{\n  a = 2;\n}\n");
+}
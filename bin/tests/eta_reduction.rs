@@ -16,4 +16,6 @@ generate_tests! {
         // don't reduce on more complex lambda bodies
         "map (x: builtins.div 3 x) [1 2 3]",
     ],
+    // eta reductions may change the behavior of the code
+    unsafe_fixes: true,
 }
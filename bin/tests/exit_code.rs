@@ -0,0 +1,51 @@
+mod _utils;
+
+use _utils::Tree;
+
+fn check(expression: &str, args: &[&str]) -> Option<i32> {
+    let tree = Tree::new(&[("default.nix", expression)]);
+    let args = [&["check"], args, &["default.nix"]].concat();
+    tree.run(&args).code
+}
+
+// raises `manual_inherit` twice, a warning
+const WARNINGS: &str = "let a = 1; b = 2; in { a = a; b = b; }";
+
+#[test]
+fn pass() {
+    assert_eq!(check("null", &[]), Some(0));
+}
+
+#[test]
+fn syntax_error() {
+    assert_eq!(check("let in in", &[]), Some(1));
+}
+
+#[test]
+fn warnings() {
+    assert_eq!(check(WARNINGS, &[]), Some(3));
+    assert_eq!(check(WARNINGS, &["--deny", "error"]), Some(0));
+}
+
+#[test]
+fn max_warnings() {
+    assert_eq!(check(WARNINGS, &["--max-warnings", "1"]), Some(3));
+    assert_eq!(check(WARNINGS, &["--max-warnings", "2"]), Some(0));
+}
+
+#[test]
+fn tool_failure() {
+    assert_eq!(check("null", &["--config", "/does/not/exist"]), Some(2));
+}
+
+// scripts compare with these codes, they are part of the interface
+#[test]
+fn default_exit_codes_are_stable() {
+    assert_eq!(check("null", &[]), Some(0));
+    assert_eq!(check("let in in", &[]), Some(1));
+    assert_eq!(check("null", &["--config", "/does/not/exist"]), Some(2));
+    assert_eq!(check(WARNINGS, &[]), Some(3));
+
+    let help = Tree::new(&[]).run(&["check", "--help"]).stdout;
+    assert!(help.contains("EXIT CODES:"));
+}
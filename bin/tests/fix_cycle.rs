@@ -0,0 +1,92 @@
+mod _utils;
+
+use std::collections::HashMap;
+
+use clap::Parser as _;
+use lib::{
+    Context, Explain, Lint, Metadata, Report, Rule, Suggestion, options::LintOption,
+    session::SessionInfo,
+};
+use rnix::{Root, SyntaxElement, SyntaxKind};
+use statix::{LintMap, config::Fix, fix::main::fix_entry, utils::ConfiguredLint};
+use vfs::ReadOnlyVfs;
+
+use _utils::Tree;
+
+// renames the identifier `from` to `to`, lints that rename back and forth
+// never converge
+struct Rename {
+    from: &'static str,
+    to: &'static str,
+}
+
+impl Metadata for Rename {
+    fn name(&self) -> &'static str {
+        "rename"
+    }
+    fn note(&self) -> &'static str {
+        "Rename"
+    }
+    fn code(&self) -> u32 {
+        99
+    }
+    fn report(&self) -> Report {
+        Report::new(self.note(), self.code())
+    }
+    fn match_with(&self, with: &SyntaxKind) -> bool {
+        *with == SyntaxKind::NODE_IDENT
+    }
+    fn match_kind(&self) -> Vec<SyntaxKind> {
+        vec![SyntaxKind::NODE_IDENT]
+    }
+    fn options(&self) -> &'static [LintOption] {
+        &[]
+    }
+}
+
+impl Explain for Rename {}
+
+impl Rule for Rename {
+    fn validate(&self, node: &SyntaxElement, _ctx: &Context) -> Option<Report> {
+        if node.to_string() != self.from {
+            return None;
+        }
+        let to = Root::parse(self.to)
+            .syntax()
+            .descendants()
+            .find(|n| n.kind() == SyntaxKind::NODE_IDENT)?;
+        let at = node.text_range();
+        Some(
+            self.report()
+                .suggest(at, "rename", Suggestion::with_replacement(at, to)),
+        )
+    }
+}
+
+impl Lint for Rename {}
+
+// `a` becomes `b`, which then flips between `b` and `c`
+static RENAMES: [Rename; 3] = [
+    Rename { from: "a", to: "b" },
+    Rename { from: "b", to: "c" },
+    Rename { from: "c", to: "b" },
+];
+
+#[test]
+fn cycles_are_not_written() {
+    let tree = Tree::new(&[("default.nix", "a")]);
+    let path = tree.join("default.nix");
+    let fix_config = Fix::parse_from(["fix", path.to_str().unwrap()]);
+    let vfs = ReadOnlyVfs::singleton(&path, b"a");
+    let lints: LintMap = HashMap::from([(
+        SyntaxKind::NODE_IDENT,
+        RENAMES
+            .iter()
+            .map(|lint| ConfiguredLint::new(lint as &dyn Lint))
+            .collect(),
+    )]);
+
+    let entry = vfs.iter().next().unwrap();
+    fix_entry(&fix_config, &entry, &lints, &SessionInfo::default(), None).unwrap();
+    assert_eq!(tree.read("default.nix"), "a");
+}
@@ -0,0 +1,43 @@
+mod _utils;
+
+use serde_json::Value;
+
+use _utils::Tree;
+
+const SRC: &str = "let a.b = 2; in { b = (a /* the set */).b; c = c; d = let in 1; }\n";
+
+fn fix(args: &[&str]) -> (String, String) {
+    let tree = Tree::new(&[("default.nix", SRC)]);
+    let output = tree.run(&[&["fix"], args, &["default.nix"]].concat());
+    assert_eq!(output.code, Some(0));
+    (output.stdout, tree.read("default.nix"))
+}
+
+#[test]
+fn edits_apply_to_the_original_source() {
+    let (stdout, contents) = fix(&["--format", "json"]);
+    assert_eq!(contents, SRC, "files are not touched");
+
+    let out: Value = serde_json::from_str(&stdout).unwrap();
+    let edits = out["edits"].as_array().unwrap();
+    let codes = edits
+        .iter()
+        .map(|e| e["code"].as_u64().unwrap())
+        .collect::<Vec<_>>();
+    // `useless_parens` and `manual_inherit_from` touch the same binding,
+    // their fixes are merged
+    assert_eq!(codes, [8, 3, 2]);
+    assert_eq!(edits[0]["fix"], "inherit (a) /* the set */ b;");
+    assert_eq!(edits[1]["at"]["from"]["column"], 44);
+
+    let mut applied = SRC.to_owned();
+    for edit in edits.iter().rev() {
+        let offset = |key: &str| usize::try_from(edit["range"][key].as_u64().unwrap()).unwrap();
+        applied.replace_range(
+            offset("start")..offset("end"),
+            edit["fix"].as_str().unwrap(),
+        );
+    }
+    let (_, fixed) = fix(&[]);
+    assert_eq!(applied, fixed);
+}
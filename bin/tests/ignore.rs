@@ -0,0 +1,66 @@
+mod _utils;
+
+use std::path::Path;
+
+use _utils::Tree;
+
+// every file contains a finding, so the files that are linted are exactly
+// the files that are reported
+fn linted_files(root: &Tree, home: &Tree, args: &[&str]) -> Vec<String> {
+    let mut command = root.command(&[&["check", "-o", "errfmt", "."], args].concat());
+    command
+        .env("HOME", home.path())
+        .env("XDG_CONFIG_HOME", home.join(".config"));
+    let mut files = root
+        .output(&mut command, "")
+        .stdout
+        .lines()
+        .filter_map(|line| line.split_once('>'))
+        .map(|(path, _)| {
+            let path = Path::new(path).strip_prefix(".").unwrap();
+            path.to_string_lossy().into_owned()
+        })
+        .collect::<Vec<_>>();
+    files.sort();
+    files
+}
+
+#[test]
+fn respects_ignore_files() {
+    let home = Tree::new(&[(".config/git/ignore", "global.nix\n")]);
+    let root = Tree::new(&[
+        (".git/info/exclude", "excluded.nix\n"),
+        (".gitignore", "vendor/\n"),
+        ("sub/.gitignore", "generated-*.nix\n!generated-keep.nix\n"),
+        ("sub/.ignore", "scratch.nix\n"),
+    ]);
+    for file in [
+        "default.nix",
+        "global.nix",
+        "excluded.nix",
+        "vendor/pkg.nix",
+        "sub/default.nix",
+        "sub/generated-a.nix",
+        "sub/generated-keep.nix",
+        "sub/scratch.nix",
+        "sub/deeper/generated-b.nix",
+        "other/generated-c.nix",
+    ] {
+        root.write(file, "let in null");
+    }
+
+    assert_eq!(
+        linted_files(&root, &home, &[]),
+        [
+            "default.nix",
+            "other/generated-c.nix",
+            "sub/default.nix",
+            "sub/generated-keep.nix",
+        ]
+    );
+    assert_eq!(
+        linted_files(&root, &home, &["--ignore", "sub"]),
+        ["default.nix", "other/generated-c.nix"]
+    );
+    assert_eq!(linted_files(&root, &home, &["--unrestricted"]).len(), 10);
+}
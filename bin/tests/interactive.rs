@@ -0,0 +1,81 @@
+mod _utils;
+
+use indoc::indoc;
+
+use _utils::Tree;
+
+const SRC: &str = indoc! {"
+    {
+      a = let in 1;
+      b = let in 2;
+      c = x: f x;
+      d = y: g y;
+    }
+"};
+
+// runs `fix --interactive --unsafe-fixes` with `answers` on stdin,
+// returns the prompts along with the fixed file
+fn fix_interactive(answers: &str) -> (String, String) {
+    fix_interactive_with(&["--unsafe-fixes"], answers)
+}
+
+fn fix_interactive_with(args: &[&str], answers: &str) -> (String, String) {
+    let tree = Tree::new(&[("default.nix", SRC)]);
+    let mut command = tree.command(&[&["fix", "--interactive", "default.nix"], args].concat());
+    let output = tree.output(&mut command, answers);
+    assert_eq!(output.code, Some(0));
+    (output.stdout, tree.read("default.nix"))
+}
+
+#[test]
+fn skip_apply_quit() {
+    let (prompts, fixed) = fix_interactive("n\ny\nq\n");
+    insta::assert_snapshot!(prompts);
+    assert_eq!(
+        fixed,
+        indoc! {"
+            {
+              a = let in 1;
+              b = 2;
+              c = x: f x;
+              d = y: g y;
+            }
+        "}
+    );
+}
+
+#[test]
+fn apply_all_of_lint() {
+    // accepting all `eta_reduction` fixes skips the prompt for `d`, the
+    // end of input quits
+    let (_, fixed) = fix_interactive("n\nn\na\n");
+    assert_eq!(
+        fixed,
+        indoc! {"
+            {
+              a = let in 1;
+              b = let in 2;
+              c = f;
+              d = g;
+            }
+        "}
+    );
+}
+
+#[test]
+fn unsafe_fixes_are_not_offered_by_default() {
+    // only the `empty_let_in` fixes are asked about, not `eta_reduction`
+    let (prompts, fixed) = fix_interactive_with(&[], "y\ny\ny\n");
+    assert_eq!(prompts.matches("Apply this fix?").count(), 2, "{prompts}");
+    assert_eq!(
+        fixed,
+        indoc! {"
+            {
+              a = 1;
+              b = 2;
+              c = x: f x;
+              d = y: g y;
+            }
+        "}
+    );
+}
@@ -13,6 +13,16 @@ generate_tests! {
               x = "hello,";
               y = " world!";
             }
-        "#}
+        "#},
+        indoc! {r#"
+            {
+              greeting = let {
+                # the greeting
+                body = x + y;
+                x = "hello,";
+                y = " world!";
+              };
+            }
+        "#},
     ],
 }
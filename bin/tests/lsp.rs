@@ -0,0 +1,150 @@
+#![cfg(feature = "lsp")]
+
+mod _utils;
+
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    path::Path,
+    process::{Child, ChildStdin, ChildStdout, Stdio},
+};
+
+use serde_json::{Value, json};
+
+use _utils::Tree;
+
+struct Client {
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl Client {
+    fn send(&mut self, message: &Value) {
+        let body = message.to_string();
+        write!(self.stdin, "Content-Length: {}\r\n\r\n{body}", body.len()).unwrap();
+        self.stdin.flush().unwrap();
+    }
+
+    fn recv(&mut self) -> Value {
+        let mut length = 0;
+        loop {
+            let mut header = String::new();
+            self.stdout.read_line(&mut header).unwrap();
+            let header = header.trim();
+            if header.is_empty() {
+                break;
+            }
+            if let Some(value) = header.strip_prefix("Content-Length: ") {
+                length = value.parse().unwrap();
+            }
+        }
+        let mut body = vec![0; length];
+        self.stdout.read_exact(&mut body).unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+}
+
+fn pretty(message: &Value) -> String {
+    serde_json::to_string_pretty(message).unwrap()
+}
+
+// spawns `statix lsp` in `dir`, and initializes it
+fn start(dir: &Path) -> (Child, Client) {
+    let mut child = _utils::statix()
+        .arg("lsp")
+        .current_dir(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut client = Client {
+        stdin: child.stdin.take().unwrap(),
+        stdout: BufReader::new(child.stdout.take().unwrap()),
+    };
+    client.send(&json!({
+        "jsonrpc": "2.0", "id": 1, "method": "initialize",
+        "params": { "capabilities": {} }
+    }));
+    client.recv();
+    client.send(&json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }));
+    (child, client)
+}
+
+fn stop(mut child: Child, mut client: Client) {
+    client.send(&json!({ "jsonrpc": "2.0", "id": 3, "method": "shutdown" }));
+    client.recv();
+    client.send(&json!({ "jsonrpc": "2.0", "method": "exit" }));
+    assert!(child.wait().unwrap().success());
+}
+
+#[test]
+fn diagnostics_and_code_actions() {
+    let tree = Tree::new(&[]);
+    let (child, mut client) = start(tree.path());
+    let uri = "file:///statix/lsp.nix";
+
+    client.send(&json!({
+        "jsonrpc": "2.0", "method": "textDocument/didOpen",
+        "params": {
+            "textDocument": {
+                "uri": uri, "languageId": "nix", "version": 1,
+                "text": "let a = 2; in { a = a; }\n"
+            }
+        }
+    }));
+    insta::assert_snapshot!("did_open", pretty(&client.recv()));
+
+    client.send(&json!({
+        "jsonrpc": "2.0", "id": 2, "method": "textDocument/codeAction",
+        "params": {
+            "textDocument": { "uri": uri },
+            "range": {
+                "start": { "line": 0, "character": 18 },
+                "end": { "line": 0, "character": 18 }
+            },
+            "context": { "diagnostics": [] }
+        }
+    }));
+    insta::assert_snapshot!("code_action", pretty(&client.recv()));
+
+    client.send(&json!({
+        "jsonrpc": "2.0", "method": "textDocument/didChange",
+        "params": {
+            "textDocument": { "uri": uri, "version": 2 },
+            "contentChanges": [{ "text": "let a = 2; in { inherit a; }\n" }]
+        }
+    }));
+    insta::assert_snapshot!("did_change", pretty(&client.recv()));
+
+    stop(child, client);
+}
+
+#[test]
+fn config_of_each_document() {
+    let tree = Tree::new(&[("sub/statix.toml", r#"disabled = ["manual_inherit"]"#)]);
+    let (child, mut client) = start(tree.path());
+
+    // returns the codes of the diagnostics published for `path`
+    let mut open = |path: &str| {
+        let uri = format!("file://{}", tree.join(path).display());
+        client.send(&json!({
+            "jsonrpc": "2.0", "method": "textDocument/didOpen",
+            "params": {
+                "textDocument": {
+                    "uri": uri, "languageId": "nix", "version": 1,
+                    "text": "let a = 2; in { a = a; }\n"
+                }
+            }
+        }));
+        let published = client.recv();
+        published["params"]["diagnostics"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|d| d["code"].as_str().unwrap().to_owned())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(open("sub/a.nix"), Vec::<String>::new());
+    assert_eq!(open("b.nix"), ["W03"]);
+
+    stop(child, client);
+}
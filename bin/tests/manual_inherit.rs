@@ -1,5 +1,7 @@
 mod _utils;
 
+use indoc::indoc;
+
 use macros::generate_tests;
 
 generate_tests! {
@@ -7,6 +9,17 @@ generate_tests! {
     expressions: [
         // trivial
         "let a = 2; in { a = a; }",
+        // comments are kept
+        "let a = 2; in { a = /* the a */ a; }",
+        indoc! {"
+            let
+              a = 2;
+            in
+            {
+              a = # the a
+                a;
+            }
+        "},
         // don't lint
         "let y = 2; in { x.y = y; }",
     ],
@@ -7,6 +7,8 @@ generate_tests! {
     expressions: [
         "let a.b = 2; in { b = a.b; }",
         "let a.b = 2; in { c = a.c; }",
+        "let a.b = 2; in { b = /* from a */ a.b; }",
+        "let a.b = 2; in { b = (a /* the set */).b; }",
         "let a.b = 2; in { b = a.c; }",
     ],
 }
@@ -0,0 +1,37 @@
+mod _utils;
+
+use _utils::Tree;
+
+// lints run in the order of their codes, reports are listed in the order
+// they appear in instead
+const SRC: &str = "{ a = let in 1; b = (c); d = d; }\n";
+
+fn check(tree: &Tree, format: &str) -> String {
+    tree.run(&["check", "-o", format, "."]).stdout
+}
+
+#[test]
+fn sorted_by_path_and_position() {
+    let tree = Tree::new(&[
+        ("z.nix", SRC),
+        ("a.nix", SRC),
+        ("m/b.nix", SRC),
+        ("m.nix", SRC),
+    ]);
+
+    let stdout = check(&tree, "errfmt");
+    let reports = stdout
+        .lines()
+        .map(|line| line.split(':').take(2).collect::<Vec<_>>().join(":"))
+        .collect::<Vec<_>>();
+    // paths are compared component by component
+    let expected = ["./a.nix", "./m/b.nix", "./m.nix", "./z.nix"]
+        .iter()
+        .flat_map(|path| ["1:7", "1:21", "1:26"].map(|at| format!("{path}>{at}")))
+        .collect::<Vec<_>>();
+    assert_eq!(reports, expected);
+
+    for format in ["errfmt", "sarif", "stderr"] {
+        assert_eq!(check(&tree, format), check(&tree, format));
+    }
+}
@@ -0,0 +1,71 @@
+mod _utils;
+
+use serde_json::Value;
+
+use _utils::Tree;
+
+#[test]
+fn results() {
+    let stdout = _utils::test_cli(
+        "let a = 1; in { a = a; b = (1); c = let in null; }",
+        &["check", "-o", "sarif"],
+    )
+    .unwrap();
+    let sarif: Value = serde_json::from_str(&stdout).unwrap();
+    let run = &sarif["runs"][0];
+
+    assert_eq!(sarif["version"], "2.1.0");
+    let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+    assert!(rules.iter().any(|rule| rule["name"] == "manual_inherit"));
+
+    insta::assert_snapshot!(serde_json::to_string_pretty(&run["results"]).unwrap());
+}
+
+#[test]
+fn single_document() {
+    let tree = Tree::new(&[("a.nix", "let in null"), ("b.nix", "let in null")]);
+    let stdout = tree.run(&["check", "-o", "sarif", "."]).stdout;
+    let sarif: Value = serde_json::from_str(&stdout).unwrap();
+
+    assert_eq!(sarif["runs"].as_array().unwrap().len(), 1);
+    assert_eq!(sarif["runs"][0]["results"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn multiple_replacements() {
+    let stdout = _utils::test_cli("let inherit a b c; in b", &["check", "-o", "sarif"]).unwrap();
+    let sarif: Value = serde_json::from_str(&stdout).unwrap();
+    let result = &sarif["runs"][0]["results"][0];
+    let replacements = &result["fixes"][0]["artifactChanges"][0]["replacements"];
+
+    let columns = replacements
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| {
+            let region = &r["deletedRegion"];
+            (region["startColumn"].as_u64(), region["endColumn"].as_u64())
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(columns, [(Some(12), Some(14)), (Some(16), Some(18))]);
+}
+
+#[test]
+fn rule_levels() {
+    let tree = Tree::new(&[
+        ("statix.toml", "[severity]\nmanual_inherit = \"error\""),
+        ("default.nix", "let a = 1; in { a = a; b = (a); }"),
+    ]);
+    let stdout = tree.run(&["check", "-o", "sarif", "."]).stdout;
+    let sarif: Value = serde_json::from_str(&stdout).unwrap();
+    let run = &sarif["runs"][0];
+    let rule_level = |name: &str| {
+        let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+        let rule = rules.iter().find(|rule| rule["name"] == name).unwrap();
+        rule["defaultConfiguration"]["level"].clone()
+    };
+
+    assert_eq!(rule_level("manual_inherit"), "error");
+    assert_eq!(rule_level("useless_parens"), "warning");
+    assert_eq!(rule_level("syntax_error"), "error");
+}
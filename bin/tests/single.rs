@@ -0,0 +1,19 @@
+mod _utils;
+
+use _utils::Tree;
+
+#[test]
+fn uses_config_of_file() {
+    let tree = Tree::new(&[
+        ("sub/statix.toml", r#"disabled = ["empty_let_in"]"#),
+        ("sub/a.nix", "let in 1\n"),
+        ("b.nix", "let in 1\n"),
+    ]);
+
+    let output = tree.run(&["single", "-p", "1,1", "sub/a.nix"]);
+    assert_eq!(output.code, Some(2), "{}", output.stderr);
+    assert_eq!(tree.read("sub/a.nix"), "let in 1\n");
+
+    let output = tree.run(&["single", "-p", "1,1", "--dry-run", "b.nix"]);
+    assert!(output.stdout.contains("+1"), "{}", output.stdout);
+}
@@ -0,0 +1,86 @@
+mod _utils;
+
+use _utils::Tree;
+
+// paths of reported files, relative to the tree, along with the lint codes
+// raised in them
+fn check(tree: &Tree, args: &[&str], stdin: &str) -> Vec<String> {
+    let mut command = tree.command(&[&["check", "-o", "errfmt"], args].concat());
+    let mut reports = tree
+        .output(&mut command, stdin)
+        .stdout
+        .lines()
+        .filter_map(|line| {
+            let (path, rest) = line.split_once('>')?;
+            let code = rest.split(':').nth(3)?;
+            let path = path.strip_prefix("./").unwrap_or(path);
+            Some(format!("{path} W{code}"))
+        })
+        .collect::<Vec<_>>();
+    reports.sort();
+    reports
+}
+
+fn setup() -> Tree {
+    Tree::new(&[
+        ("a.nix", "let in null"),
+        ("b.nix", "let in null"),
+        ("c.nix", "let in null"),
+        ("sub/d.nix", "let in null"),
+    ])
+}
+
+#[test]
+fn multiple_targets() {
+    let tree = setup();
+    assert_eq!(
+        check(&tree, &["a.nix", "sub"], ""),
+        ["a.nix W2", "sub/d.nix W2"]
+    );
+}
+
+#[test]
+fn overlapping_targets() {
+    let tree = setup();
+    assert_eq!(
+        check(&tree, &["a.nix", "./a.nix", "sub", "sub/d.nix"], ""),
+        ["a.nix W2", "sub/d.nix W2"]
+    );
+}
+
+#[test]
+fn files_from_stdin() {
+    let tree = setup();
+    assert_eq!(
+        check(&tree, &["--files-from", "-"], "a.nix\nsub/d.nix\n"),
+        ["a.nix W2", "sub/d.nix W2"]
+    );
+    // NUL separated, as with `git diff -z`, removed files are skipped
+    assert_eq!(
+        check(&tree, &["--files-from", "-"], "b.nix\0removed.nix\0"),
+        ["b.nix W2"]
+    );
+}
+
+#[test]
+fn files_from_file() {
+    let tree = setup();
+    tree.write("files.txt", "c.nix\n");
+    assert_eq!(
+        check(&tree, &["a.nix", "--files-from", "files.txt"], ""),
+        ["a.nix W2", "c.nix W2"]
+    );
+}
+
+#[test]
+fn config_is_discovered_per_file() {
+    let tree = setup();
+    tree.write("sub/statix.toml", "disabled = [\"empty_let_in\"]\n");
+    assert_eq!(check(&tree, &["a.nix", "sub"], ""), ["a.nix W2"]);
+
+    // an explicit config applies to every file
+    assert_eq!(
+        check(&tree, &["a.nix", "sub", "-c", "sub"], ""),
+        Vec::<String>::new()
+    );
+}
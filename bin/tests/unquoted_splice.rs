@@ -0,0 +1,17 @@
+mod _utils;
+
+use macros::generate_tests;
+
+generate_tests! {
+    rule: unquoted_splice,
+    expressions: [
+        // match
+        "{ b = c.${d}; }",
+        "let pkgs = nixpkgs.legacyPackages.${system}; in pkgs",
+
+        // don't match
+        r#"{ b = c."${d}"; }"#,
+    ],
+    // quotes are optional from Nix 2.4 onwards
+    config: r#"nix_version = "2.3""#,
+}
@@ -0,0 +1,25 @@
+mod _utils;
+
+use indoc::indoc;
+
+use macros::generate_tests;
+
+generate_tests! {
+    rule: unused_lambda_argument,
+    expressions: [
+        "{ config, lib, pkgs, ... }: pkgs.hello",
+        "{ a, ... }: null",
+        "{ a ? 1, b ? a, ... }: b",
+        "{ _a, ... }: null",
+        "{ a, b }: a",
+        "args@{ a, ... }: args",
+        "args@{ a, ... }: null",
+        indoc! {"
+            { a
+            , b
+            , ...
+            }:
+            b
+        "},
+    ],
+}
@@ -0,0 +1,37 @@
+mod _utils;
+
+use indoc::indoc;
+
+use macros::generate_tests;
+
+generate_tests! {
+    rule: unused_let_binding,
+    expressions: [
+        "let a = 1; in null",
+        "let a = 1; b = a; in b",
+        "let f = x: f x; in null",
+        "let _a = 1; in null",
+        "let inherit (pkgs) hello cowsay; in hello",
+        "let inherit a b; in null",
+        "let inherit a b c; in b",
+        "let inherit (pkgs) a \"b\" c; in c",
+        "let a.b = 1; a.c = 2; in a",
+        indoc! {"
+            let
+              # don't fix this, we have a comment
+              a = /* comment */ 1;
+              b = 2;
+            in
+            b
+        "},
+        indoc! {"
+            let
+              # the answer
+              a = 42;
+              b = 2; # two
+              c = 3;
+            in
+            null
+        "},
+    ],
+}
@@ -0,0 +1,58 @@
+mod _utils;
+
+use indoc::indoc;
+
+use macros::generate_tests;
+
+generate_tests! {
+    rule: unused_suppression,
+    expressions: [
+        // suppress the next line
+        indoc! {"
+            {
+              # statix: allow(manual_inherit)
+              a = a;
+              b = b;
+            }
+        "},
+
+        // suppress the current line
+        indoc! {"
+            {
+              a = a; # statix: allow(manual_inherit)
+              b = b;
+            }
+        "},
+
+        // suppress within the following binding
+        indoc! {"
+            {
+              # statix: allow(manual_inherit)
+              a = {
+                b = b;
+                c = c;
+              };
+            }
+        "},
+
+        // suppress in the entire file
+        indoc! {"
+            # statix: allow-file(manual_inherit)
+            {
+              a = a;
+              b = { c = c; };
+            }
+        "},
+
+        // unused and unknown lints
+        indoc! {"
+            {
+              # statix: allow(manual_inherit, useless_parens, no_such_lint)
+              a = a;
+            }
+        "},
+
+        // malformed
+        "{ a = 1; /* statix: allow manual_inherit */ }",
+    ],
+}
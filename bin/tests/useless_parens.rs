@@ -13,6 +13,9 @@ generate_tests! {
         // parens around let-value
         "let a = (1 + 2); in null",
         "let h = ({ inherit (builtins) map; }); in null",
+        // comments within the parens are kept
+        "let a = (/* three */ 1 + 2); in null",
+        "let a = (1 + 2 # three\n); in null",
 
         // LATER: binary exprs, function args etc.
 
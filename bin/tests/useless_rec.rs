@@ -0,0 +1,22 @@
+mod _utils;
+
+use indoc::indoc;
+
+use macros::generate_tests;
+
+generate_tests! {
+    rule: useless_rec,
+    expressions: [
+        "rec { a = 1; b = 2; }",
+        "rec { a = 1; b = a; }",
+        "rec { a.b = 1; c = a.b; }",
+        "a: rec { inherit a; b = a; }",
+        "rec{ a = 1; }",
+        indoc! {"
+            rec # keep the comment
+            {
+              a = 1;
+            }
+        "},
+    ],
+}
@@ -0,0 +1,88 @@
+mod _utils;
+
+use std::{
+    fs,
+    io::{self, BufRead, BufReader},
+    process::Child,
+    sync::mpsc::{self, Receiver},
+    thread,
+    time::Duration,
+};
+
+use _utils::Tree;
+
+// `statix --watch` does not exit by itself, it is killed when the test
+// ends, whether it passes or not
+struct KillOnDrop(Child);
+
+impl Drop for KillOnDrop {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+// findings printed by the next run, along with its status line
+fn next_run(lines: &Receiver<String>) -> (Vec<String>, String) {
+    let mut findings = Vec::new();
+    loop {
+        let line = lines.recv_timeout(Duration::from_mins(1)).unwrap();
+        if line.contains("watching for changes") {
+            return (findings, line);
+        } else if line.contains(".nix>") {
+            findings.push(line);
+        }
+    }
+}
+
+#[test]
+fn relints_changed_files() {
+    let tree = Tree::new(&[
+        (".gitignore", "ignored.nix\n"),
+        ("a.nix", "null"),
+        ("b.nix", "let in null"),
+        ("ignored.nix", "null"),
+    ]);
+
+    // stderr is merged into stdout, to preserve the order of findings and
+    // status lines
+    let (output, writer) = io::pipe().unwrap();
+    let child = tree
+        .command(&["check", "--watch", "-o", "errfmt", "."])
+        .stdout(writer.try_clone().unwrap())
+        .stderr(writer)
+        .spawn()
+        .unwrap();
+    let _child = KillOnDrop(child);
+    let (tx, lines) = mpsc::channel();
+    thread::spawn(move || {
+        for line in BufReader::new(output).lines() {
+            let Ok(line) = line else { break };
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    let (findings, status) = next_run(&lines);
+    assert_eq!(findings.len(), 1, "{findings:?}");
+    assert!(findings[0].contains("b.nix"));
+    assert!(status.starts_with("1 file(s)"));
+
+    tree.write("ignored.nix", "let in null");
+    thread::sleep(Duration::from_millis(500));
+    tree.write("a.nix", "let in null");
+    fs::remove_file(tree.join("b.nix")).unwrap();
+
+    // events may be split across several runs
+    let (findings, status) = loop {
+        let (findings, status) = next_run(&lines);
+        if findings.iter().all(|line| !line.contains("b.nix")) {
+            break (findings, status);
+        }
+    };
+
+    assert_eq!(findings.len(), 1, "{findings:?}");
+    assert!(findings[0].contains("a.nix"));
+    assert!(status.starts_with("1 file(s)"));
+}
@@ -1,11 +1,18 @@
 #![recursion_limit = "1024"]
 mod lints;
 mod make;
+pub mod options;
+pub mod semantic;
+pub mod session;
+mod suppression;
 mod utils;
 
 pub use lints::LINTS;
+pub use suppression::{Allowed, Suppressions};
 
-use rnix::{SyntaxElement, SyntaxKind, TextRange, parser::ParseError};
+use rnix::{SyntaxElement, SyntaxKind, SyntaxNode, TextRange, parser::ParseError};
+use semantic::SemanticModel;
+use session::SessionInfo;
 use std::{convert::Into, default::Default};
 
 #[cfg(feature = "json-out")]
@@ -14,17 +21,42 @@ use serde::{
     ser::{SerializeStruct, Serializer},
 };
 
-#[derive(Debug, Default)]
+/// Severity of a report, ordered from least to most severe
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[cfg_attr(feature = "json-out", derive(Serialize))]
 pub enum Severity {
+    Hint,
     #[default]
     Warn,
     Error,
-    Hint,
+}
+
+impl std::str::FromStr for Severity {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "warn" | "warning" => Ok(Self::Warn),
+            "error" => Ok(Self::Error),
+            "hint" => Ok(Self::Hint),
+            _ => Err(format!(
+                "unknown severity `{s}`, expected one of `error`, `warn` or `hint`"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Warn => write!(f, "warn"),
+            Self::Error => write!(f, "error"),
+            Self::Hint => write!(f, "hint"),
+        }
+    }
 }
 
 /// Report generated by a lint
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 #[cfg_attr(feature = "json-out", derive(Serialize))]
 pub struct Report {
     /// General information about this lint and where it applies.
@@ -75,7 +107,7 @@ impl Report {
     pub fn total_suggestion_range(&self) -> Option<TextRange> {
         self.diagnostics
             .iter()
-            .filter_map(|d| Some(d.suggestion.as_ref()?.at))
+            .filter_map(|d| Some(d.suggestion.as_ref()?.at()))
             .reduce(rnix::TextRange::cover)
     }
     /// A range that encompasses all the diagnostics provided in this report
@@ -90,10 +122,33 @@ impl Report {
     pub fn range(&self) -> TextRange {
         self.total_suggestion_range().unwrap()
     }
-    /// Apply all diagnostics. Assumption: diagnostics do not overlap
+    /// The least confident applicability among the suggestions of this
+    /// report
+    #[must_use]
+    pub fn applicability(&self) -> Option<Applicability> {
+        self.diagnostics
+            .iter()
+            .filter_map(|d| Some(d.suggestion.as_ref()?.applicability))
+            .max()
+    }
+    /// The edits of all the suggestions in this report, front to back
+    #[must_use]
+    pub fn edits(&self) -> Vec<&TextEdit> {
+        let mut edits = self
+            .diagnostics
+            .iter()
+            .filter_map(|d| d.suggestion.as_ref())
+            .flat_map(|s| &s.edits)
+            .collect::<Vec<_>>();
+        edits.sort_by_key(|e| e.at.start());
+        edits
+    }
+    /// Apply all suggestions at once. Edits are applied back to front, so
+    /// that the ranges of the remaining edits stay valid. Assumption: edits
+    /// do not overlap
     pub fn apply(&self, src: &mut String) {
-        for d in &self.diagnostics {
-            d.apply(src);
+        for edit in self.edits().into_iter().rev() {
+            edit.apply(src);
         }
     }
     /// Create a report out of a parse error
@@ -124,7 +179,7 @@ impl Report {
 
 /// Mapping from a bytespan to an error message.
 /// Can optionally suggest a fix.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Diagnostic {
     pub at: TextRange,
     pub message: String,
@@ -177,30 +232,43 @@ impl Serialize for Diagnostic {
     }
 }
 
-#[derive(Debug)]
+/// How confident a lint is that its suggestion preserves the meaning of
+/// the code, ordered from most to least confident
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "json-out", derive(Serialize))]
+pub enum Applicability {
+    /// The suggestion is what the user intended, it is applied by
+    /// `statix fix`
+    #[default]
+    MachineApplicable,
+    /// The suggestion may change the behavior of the code, for example
+    /// with respect to laziness, it has to be opted in to
+    MaybeIncorrect,
+}
+
+impl std::fmt::Display for Applicability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MachineApplicable => write!(f, "MachineApplicable"),
+            Self::MaybeIncorrect => write!(f, "MaybeIncorrect"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub enum Replacement {
     Empty,
     SyntaxElement(SyntaxElement),
 }
 
-/// Suggested fix for a diagnostic, the fix is provided as a syntax element.
-/// Look at `make.rs` to construct fixes.
-#[derive(Debug)]
-pub struct Suggestion {
+/// Replacement of a single range of the source
+#[derive(Debug, Clone)]
+pub struct TextEdit {
     pub at: TextRange,
     pub fix: Replacement,
 }
 
-impl std::fmt::Display for Replacement {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Replacement::Empty => Ok(()),
-            Replacement::SyntaxElement(syntax_element) => write!(f, "{syntax_element}"),
-        }
-    }
-}
-
-impl Suggestion {
+impl TextEdit {
     #[must_use]
     pub fn with_replacement(at: TextRange, fix: impl Into<SyntaxElement>) -> Self {
         Self {
@@ -215,7 +283,7 @@ impl Suggestion {
             fix: Replacement::Empty,
         }
     }
-    /// Apply a suggestion to a source file
+    /// Apply an edit to a source file
     pub fn apply(&self, src: &mut String) {
         let start = usize::from(self.at.start());
         let end = usize::from(self.at.end());
@@ -223,15 +291,13 @@ impl Suggestion {
     }
 }
 
-unsafe impl Send for Suggestion {}
-
 #[cfg(feature = "json-out")]
-impl Serialize for Suggestion {
+impl Serialize for TextEdit {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let mut s = serializer.serialize_struct("Suggestion", 2)?;
+        let mut s = serializer.serialize_struct("TextEdit", 2)?;
         let at = {
             let start = usize::from(self.at.start());
             let end = usize::from(self.at.end());
@@ -244,10 +310,129 @@ impl Serialize for Suggestion {
     }
 }
 
+/// Suggested fix for a diagnostic, made of one or more edits that are
+/// applied together. Edits are ordered front to back and do not overlap,
+/// the fix of each edit is provided as a syntax element. Look at `make.rs`
+/// to construct fixes.
+#[derive(Debug, Clone)]
+pub struct Suggestion {
+    pub edits: Vec<TextEdit>,
+    pub applicability: Applicability,
+}
+
+impl std::fmt::Display for Replacement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Replacement::Empty => Ok(()),
+            Replacement::SyntaxElement(syntax_element) => write!(f, "{syntax_element}"),
+        }
+    }
+}
+
+impl Suggestion {
+    #[must_use]
+    pub fn with_replacement(at: TextRange, fix: impl Into<SyntaxElement>) -> Self {
+        Self::of(vec![TextEdit::with_replacement(at, fix)])
+    }
+    #[must_use]
+    pub fn with_empty(at: TextRange) -> Self {
+        Self::of(vec![TextEdit::with_empty(at)])
+    }
+    /// Construct a suggestion out of several edits, in any order. Returns
+    /// `None` if there are no edits, or if two edits overlap.
+    #[must_use]
+    pub fn with_edits(mut edits: Vec<TextEdit>) -> Option<Self> {
+        edits.sort_by_key(|e| e.at.start());
+        let disjoint = edits.windows(2).all(|w| w[0].at.end() <= w[1].at.start());
+        (!edits.is_empty() && disjoint).then(|| Self::of(edits))
+    }
+    fn of(edits: Vec<TextEdit>) -> Self {
+        Self {
+            edits,
+            applicability: Applicability::default(),
+        }
+    }
+    #[must_use]
+    pub fn with_applicability(mut self, applicability: Applicability) -> Self {
+        self.applicability = applicability;
+        self
+    }
+    /// A range that encompasses all the edits of this suggestion
+    #[must_use]
+    pub fn at(&self) -> TextRange {
+        self.edits
+            .iter()
+            .map(|e| e.at)
+            .reduce(TextRange::cover)
+            .unwrap_or_default()
+    }
+    /// Apply a suggestion to a source file, back to front
+    pub fn apply(&self, src: &mut String) {
+        for edit in self.edits.iter().rev() {
+            edit.apply(src);
+        }
+    }
+}
+
+unsafe impl Send for Suggestion {}
+
+#[cfg(feature = "json-out")]
+impl Serialize for Suggestion {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut s = serializer.serialize_struct("Suggestion", 4)?;
+        let at = {
+            let start = usize::from(self.at().start());
+            let end = usize::from(self.at().end());
+            (start, end)
+        };
+        s.serialize_field("at", &at)?;
+        match self.edits.as_slice() {
+            [edit] => s.serialize_field("fix", &edit.fix.to_string())?,
+            _ => s.skip_field("fix")?,
+        }
+        s.serialize_field("edits", &self.edits)?;
+        s.serialize_field("applicability", &self.applicability)?;
+        s.end()
+    }
+}
+
 /// Lint logic is defined via this trait. Do not implement manually,
 /// look at the `lint` attribute macro instead for implementing rules
 pub trait Rule {
-    fn validate(&self, node: &SyntaxElement) -> Option<Report>;
+    fn validate(&self, node: &SyntaxElement, ctx: &Context) -> Option<Report>;
+}
+
+/// Information about a file that is shared by all rules run on it, computed
+/// once per file
+#[derive(Debug)]
+pub struct Context {
+    semantic: SemanticModel,
+    session: SessionInfo,
+}
+
+impl Context {
+    #[must_use]
+    pub fn new(root: &SyntaxNode, session: &SessionInfo) -> Self {
+        Self {
+            semantic: SemanticModel::new(root),
+            session: session.clone(),
+        }
+    }
+
+    /// The Nix version and lint options the file is linted with
+    #[must_use]
+    pub fn session(&self) -> &SessionInfo {
+        &self.session
+    }
+
+    /// Scopes, bindings and name resolution of the file
+    #[must_use]
+    pub fn semantic(&self) -> &SemanticModel {
+        &self.semantic
+    }
 }
 
 /// Contains information about the lint itself. Do not implement manually,
@@ -259,6 +444,7 @@ pub trait Metadata {
     fn report(&self) -> Report;
     fn match_with(&self, with: &SyntaxKind) -> bool;
     fn match_kind(&self) -> Vec<SyntaxKind>;
+    fn options(&self) -> &'static [options::LintOption];
 }
 
 /// Contains offline explanation for each lint
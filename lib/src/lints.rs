@@ -9,7 +9,7 @@ lints! {
     collapsible_let_in,
     eta_reduction,
     useless_parens,
-    // unquoted_splice,
+    unquoted_splice,
     empty_pattern,
     redundant_pattern_bind,
     unquoted_uri,
@@ -18,5 +18,9 @@ lints! {
     bool_simplification,
     useless_has_attr,
     repeated_keys,
-    empty_list_concat
+    empty_list_concat,
+    unused_suppression,
+    unused_let_binding,
+    unused_lambda_argument,
+    useless_rec,
 }
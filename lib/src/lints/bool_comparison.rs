@@ -1,4 +1,4 @@
-use crate::{Metadata, Report, Rule, Suggestion, make};
+use crate::{Context, Metadata, Report, Rule, Suggestion, make};
 
 use macros::lint;
 use rnix::{
@@ -35,7 +35,7 @@ use rowan::ast::AstNode as _;
 struct BoolComparison;
 
 impl Rule for BoolComparison {
-    fn validate(&self, node: &SyntaxElement) -> Option<Report> {
+    fn validate(&self, node: &SyntaxElement, _ctx: &Context) -> Option<Report> {
         let NodeOrToken::Node(node) = node else {
             return None;
         };
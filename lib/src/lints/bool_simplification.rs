@@ -1,4 +1,4 @@
-use crate::{Metadata, Report, Rule, Suggestion, make};
+use crate::{Context, Metadata, Report, Rule, Suggestion, make};
 
 use macros::lint;
 use rnix::{
@@ -23,16 +23,21 @@ use rowan::ast::AstNode as _;
 /// ```nix
 /// if x != y then 0 else 1
 /// ```
+///
+/// ## Options
+/// - `negated_equality` (default `true`): suggest `x != y` for `!(x == y)`
+/// - `negated_inequality` (default `false`): suggest `x == y` for `!(x != y)`
 #[lint(
     name = "bool_simplification",
     note = "This boolean expression can be simplified",
     code = 18,
-    match_with = SyntaxKind::NODE_UNARY_OP
+    match_with = SyntaxKind::NODE_UNARY_OP,
+    options = [negated_equality = true, negated_inequality = false]
 )]
 struct BoolSimplification;
 
 impl Rule for BoolSimplification {
-    fn validate(&self, node: &SyntaxElement) -> Option<Report> {
+    fn validate(&self, node: &SyntaxElement, ctx: &Context) -> Option<Report> {
         let NodeOrToken::Node(node) = node else {
             return None;
         };
@@ -55,16 +60,28 @@ impl Rule for BoolSimplification {
             return None;
         };
 
-        let Some(BinOpKind::Equal) = bin_expr.operator() else {
-            return None;
+        let (option, op, message) = match bin_expr.operator()? {
+            BinOpKind::Equal => (
+                "negated_equality",
+                "!=",
+                "Try `!=` instead of `!(... == ...)`",
+            ),
+            BinOpKind::NotEqual => (
+                "negated_inequality",
+                "==",
+                "Try `==` instead of `!(... != ...)`",
+            ),
+            _ => return None,
         };
+        if !ctx.session().option::<bool>(self, option) {
+            return None;
+        }
 
         let at = node.text_range();
-        let message = "Try `!=` instead of `!(... == ...)`";
 
         let lhs = bin_expr.lhs()?;
         let rhs = bin_expr.rhs()?;
-        let replacement = make::binary(lhs.syntax(), "!=", rhs.syntax())
+        let replacement = make::binary(lhs.syntax(), op, rhs.syntax())
             .syntax()
             .clone();
         Some(
@@ -1,11 +1,11 @@
-use crate::{Metadata, Report, Rule, Suggestion};
+use crate::{Context, Metadata, Report, Rule, Suggestion, make};
 
 use macros::lint;
 use rnix::{
-    NodeOrToken, SyntaxElement, SyntaxKind, TextRange,
-    ast::{Expr, LetIn},
+    NodeOrToken, SyntaxElement, SyntaxKind, SyntaxNode,
+    ast::{Expr, HasEntry, LetIn},
 };
-use rowan::{Direction, ast::AstNode as _};
+use rowan::ast::AstNode as _;
 
 /// ## What it does
 /// Checks for `let-in` expressions whose body is another `let-in`
@@ -44,7 +44,7 @@ use rowan::{Direction, ast::AstNode as _};
 struct CollapsibleLetIn;
 
 impl Rule for CollapsibleLetIn {
-    fn validate(&self, node: &SyntaxElement) -> Option<Report> {
+    fn validate(&self, node: &SyntaxElement, _ctx: &Context) -> Option<Report> {
         let NodeOrToken::Node(node) = node else {
             return None;
         };
@@ -62,21 +62,7 @@ impl Rule for CollapsibleLetIn {
         let second_annotation = body.syntax().text_range();
         let second_message = "This `let in` expression is nested";
 
-        let replacement_at = {
-            let start = body
-                .syntax()
-                .siblings_with_tokens(Direction::Prev)
-                .find(|elem| elem.kind() == SyntaxKind::TOKEN_IN)?
-                .text_range()
-                .start();
-            let end = body
-                .syntax()
-                .descendants_with_tokens()
-                .find(|elem| elem.kind() == SyntaxKind::TOKEN_LET)?
-                .text_range()
-                .end();
-            TextRange::new(start, end)
-        };
+        let replacement = collapse(node, body.syntax())?;
 
         Some(
             self.report()
@@ -84,8 +70,48 @@ impl Rule for CollapsibleLetIn {
                 .suggest(
                     second_annotation,
                     second_message,
-                    Suggestion::with_empty(replacement_at),
+                    Suggestion::with_replacement(first_annotation, replacement),
                 ),
         )
     }
 }
+
+// the bindings of `inner` are moved up, and its body is re-indented to
+// match `outer`. Comments between the two `let in` expressions are kept
+// on lines of their own.
+fn collapse(outer: &SyntaxNode, inner: &SyntaxNode) -> Option<SyntaxNode> {
+    let mut head = outer
+        .children_with_tokens()
+        .take_while(|el| el.kind() != SyntaxKind::TOKEN_IN)
+        .collect::<Vec<_>>();
+    while head
+        .last()
+        .is_some_and(|el| el.kind() == SyntaxKind::TOKEN_WHITESPACE)
+    {
+        head.pop();
+    }
+    let between = outer
+        .children_with_tokens()
+        .skip_while(|el| el.kind() != SyntaxKind::TOKEN_IN)
+        .skip(1)
+        .take_while(|el| el.as_node() != Some(inner));
+    let tail = inner
+        .children_with_tokens()
+        .skip_while(|el| el.kind() != SyntaxKind::TOKEN_LET)
+        .skip(1);
+
+    // comments go where the bindings are
+    let outer_indent = make::indent_of(outer);
+    let binding_indent = LetIn::cast(outer.clone())?
+        .entries()
+        .next()
+        .map(|entry| make::indent_of(entry.syntax()))
+        .unwrap_or_default();
+    let extra = binding_indent
+        .strip_prefix(outer_indent.as_str())
+        .unwrap_or_default();
+
+    let fix = make::Builder::new(outer).elements(head);
+    let fix = make::comments(between).fold(fix, |fix, comment| fix.line(extra).text(&comment));
+    Some(fix.elements(tail).build::<LetIn>().syntax().clone())
+}
@@ -1,4 +1,4 @@
-use crate::{Metadata, Report, Rule};
+use crate::{Context, Metadata, Report, Rule, session::Version};
 
 use macros::lint;
 use rnix::{NodeOrToken, SyntaxElement, SyntaxKind, ast::Apply};
@@ -26,6 +26,9 @@ use rowan::ast::AstNode as _;
 /// # to convert the string to a path relative to the current directory:
 /// ./. + "/bin"
 /// # => /home/np/statix/bin
+///
+/// # to copy the path to the store, on Nix 2.0 and later:
+/// builtins.path { path = /. + "/path"; }
 /// ```
 #[lint(
     name = "deprecated_to_path",
@@ -37,17 +40,24 @@ struct DeprecatedToPath;
 
 static ALLOWED_PATHS: &[&str; 2] = &["builtins.toPath", "toPath"];
 
+// first version of Nix with `builtins.path`
+const HAS_BUILTINS_PATH: Version = Version::new(2, 0, 0);
+
 impl Rule for DeprecatedToPath {
-    fn validate(&self, node: &SyntaxElement) -> Option<Report> {
+    fn validate(&self, node: &SyntaxElement, ctx: &Context) -> Option<Report> {
         if let NodeOrToken::Node(node) = node
             && let Some(apply) = Apply::cast(node.clone())
             && let lambda_path = apply.lambda()?.to_string()
             && ALLOWED_PATHS.contains(&lambda_path.as_str())
         {
             let at = node.text_range();
-            let message = format!(
-                "`{lambda_path}` is deprecated, see `:doc builtins.toPath` within the REPL for more"
-            );
+            let message = if ctx.session().at_least(HAS_BUILTINS_PATH) {
+                format!(
+                    "`{lambda_path}` is deprecated, consider `/. + \"/path\"` or `builtins.path`, see `:doc builtins.toPath` within the REPL for more"
+                )
+            } else {
+                format!("`{lambda_path}` is deprecated, consider `/. + \"/path\"` instead")
+            };
             Some(self.report().diagnostic(at, message))
         } else {
             None
@@ -1,4 +1,4 @@
-use crate::{Metadata, Report, Rule, Suggestion};
+use crate::{Context, Metadata, Report, Rule, Suggestion};
 
 use macros::lint;
 use rnix::{
@@ -32,7 +32,7 @@ use rowan::ast::AstNode as _;
 struct EmptyListConcat;
 
 impl Rule for EmptyListConcat {
-    fn validate(&self, node: &SyntaxElement) -> Option<Report> {
+    fn validate(&self, node: &SyntaxElement, _ctx: &Context) -> Option<Report> {
         let NodeOrToken::Node(node) = node else {
             return None;
         };
@@ -1,4 +1,4 @@
-use crate::{Metadata, Report, Rule, Suggestion, make};
+use crate::{Context, Metadata, Report, Rule, Suggestion, make};
 
 use macros::lint;
 use rnix::{
@@ -41,7 +41,7 @@ use rowan::ast::AstNode as _;
 struct EmptyPattern;
 
 impl Rule for EmptyPattern {
-    fn validate(&self, node: &SyntaxElement) -> Option<Report> {
+    fn validate(&self, node: &SyntaxElement, _ctx: &Context) -> Option<Report> {
         let NodeOrToken::Node(node) = node else {
             return None;
         };
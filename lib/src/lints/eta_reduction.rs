@@ -1,4 +1,4 @@
-use crate::{Metadata, Report, Rule, Suggestion};
+use crate::{Applicability, Context, Metadata, Report, Rule, Suggestion};
 
 use macros::lint;
 use rnix::{
@@ -42,7 +42,7 @@ use rowan::ast::AstNode as _;
 struct EtaReduction;
 
 impl Rule for EtaReduction {
-    fn validate(&self, node: &SyntaxElement) -> Option<Report> {
+    fn validate(&self, node: &SyntaxElement, _ctx: &Context) -> Option<Report> {
         let NodeOrToken::Node(node) = node else {
             return None;
         };
@@ -82,11 +82,15 @@ impl Rule for EtaReduction {
         let at = node.text_range();
         let replacement = body.lambda()?;
         let message = format!("Found eta-reduction: `{}`", replacement.syntax().text());
-        Some(self.report().suggest(
-            at,
-            message,
-            Suggestion::with_replacement(at, replacement.syntax().clone()),
-        ))
+        // `x: f x` is lazy in `f`, while `f` is not
+        Some(
+            self.report().suggest(
+                at,
+                message,
+                Suggestion::with_replacement(at, replacement.syntax().clone())
+                    .with_applicability(Applicability::MaybeIncorrect),
+            ),
+        )
     }
 }
 
@@ -1,9 +1,9 @@
-use crate::{Metadata, Report, Rule, Suggestion, make};
+use crate::{Context, Metadata, Report, Rule, Suggestion, make};
 
 use macros::lint;
 use rnix::{
     NodeOrToken, SyntaxElement, SyntaxKind,
-    ast::{Attr, Entry, HasEntry, LegacyLet},
+    ast::{Attr, Entry, HasEntry, LegacyLet, Select},
 };
 use rowan::ast::AstNode as _;
 
@@ -44,7 +44,7 @@ use rowan::ast::AstNode as _;
 struct ManualInherit;
 
 impl Rule for ManualInherit {
-    fn validate(&self, node: &SyntaxElement) -> Option<Report> {
+    fn validate(&self, node: &SyntaxElement, _ctx: &Context) -> Option<Report> {
         let NodeOrToken::Node(node) = node else {
             return None;
         };
@@ -71,11 +71,16 @@ impl Rule for ManualInherit {
             return None;
         }
 
-        let inherits = legacy_let.inherits();
-        let entries = legacy_let.entries();
-        let attrset = make::attrset(inherits, entries, true);
-        let parenthesized = make::parenthesize(attrset.syntax());
-        let selected = make::select(parenthesized.syntax(), make::ident("body").syntax());
+        // the attrset is kept as written, only the keyword changes
+        let selected = make::Builder::new(node)
+            .text("rec")
+            .elements(
+                node.children_with_tokens()
+                    .skip_while(|el| el.kind() != SyntaxKind::TOKEN_LET)
+                    .skip(1),
+            )
+            .text(".body")
+            .build::<Select>();
 
         let at = node.text_range();
         let message = "Prefer `rec` over undocumented `let` syntax";
@@ -1,9 +1,9 @@
-use crate::{Metadata, Report, Rule, Suggestion, make};
+use crate::{Context, Metadata, Report, Rule, Suggestion, make};
 
 use macros::lint;
 use rnix::{
     NodeOrToken, SyntaxElement, SyntaxKind,
-    ast::{Attr, AttrpathValue, Expr},
+    ast::{Attr, AttrpathValue, Expr, Inherit},
 };
 use rowan::ast::AstNode as _;
 
@@ -40,7 +40,7 @@ use rowan::ast::AstNode as _;
 struct ManualInherit;
 
 impl Rule for ManualInherit {
-    fn validate(&self, node: &SyntaxElement) -> Option<Report> {
+    fn validate(&self, node: &SyntaxElement, _ctx: &Context) -> Option<Report> {
         let NodeOrToken::Node(node) = node else {
             return None;
         };
@@ -66,7 +66,15 @@ impl Rule for ManualInherit {
             return None;
         }
 
-        let replacement = make::inherit_stmt(&[key]).syntax().clone();
+        // comments are carried over, in front of the inherited name
+        let replacement = make::Builder::new(node)
+            .text("{ inherit ")
+            .comments(node.children_with_tokens())
+            .element(key.syntax().clone())
+            .text("; }")
+            .build::<Inherit>()
+            .syntax()
+            .clone();
 
         Some(self.report().suggest(
             node.text_range(),
@@ -1,9 +1,9 @@
-use crate::{Metadata, Report, Rule, Suggestion, make};
+use crate::{Context, Metadata, Report, Rule, Suggestion, make};
 
 use macros::lint;
 use rnix::{
     NodeOrToken, SyntaxElement, SyntaxKind,
-    ast::{Attr, AttrpathValue, Expr},
+    ast::{Attr, AttrpathValue, Expr, Inherit},
 };
 use rowan::ast::AstNode as _;
 
@@ -40,7 +40,7 @@ use rowan::ast::AstNode as _;
 struct ManualInherit;
 
 impl Rule for ManualInherit {
-    fn validate(&self, node: &SyntaxElement) -> Option<Report> {
+    fn validate(&self, node: &SyntaxElement, _ctx: &Context) -> Option<Report> {
         let NodeOrToken::Node(node) = node else {
             return None;
         };
@@ -79,9 +79,23 @@ impl Rule for ManualInherit {
 
         let at = node.text_range();
 
+        // comments outside of the set are carried over, in front of the
+        // inherited name
         let replacement = {
             let set = value.expr()?;
-            make::inherit_from_stmt(set.syntax(), &[key])
+            let set_range = set.syntax().text_range();
+            let outside_set = node
+                .descendants_with_tokens()
+                .filter(|el| el.as_token().is_some())
+                .filter(|el| !set_range.contains_range(el.text_range()));
+            make::Builder::new(node)
+                .text("{ inherit (")
+                .element(set.syntax().clone())
+                .text(") ")
+                .comments(outside_set)
+                .element(key.syntax().clone())
+                .text("; }")
+                .build::<Inherit>()
                 .syntax()
                 .clone()
         };
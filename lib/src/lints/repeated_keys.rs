@@ -1,6 +1,6 @@
 use std::fmt::Write as _;
 
-use crate::{Metadata, Report, Rule};
+use crate::{Context, Metadata, Report, Rule};
 
 use macros::lint;
 use rnix::{
@@ -35,17 +35,21 @@ use rowan::ast::AstNode as _;
 ///   };
 /// }
 /// ```
-
+///
+/// ## Options
+/// - `threshold` (default `3`): number of times a key is repeated before
+///   it is reported, at least `2`
 #[lint(
     name = "repeated_keys",
     note = "Avoid repeated keys in attribute sets",
     code = 20,
-    match_with = SyntaxKind::NODE_ATTRPATH_VALUE
+    match_with = SyntaxKind::NODE_ATTRPATH_VALUE,
+    options = [threshold(min = 2) = 3]
 )]
 struct RepeatedKeys;
 
 impl Rule for RepeatedKeys {
-    fn validate(&self, node: &SyntaxElement) -> Option<Report> {
+    fn validate(&self, node: &SyntaxElement, ctx: &Context) -> Option<Report> {
         let NodeOrToken::Node(node) = node else {
             return None;
         };
@@ -102,39 +106,42 @@ impl Rule for RepeatedKeys {
             return None;
         }
 
-        if occurrences.len() < 3 {
+        let threshold = ctx.session().option::<usize>(self, "threshold");
+        if occurrences.len() < threshold {
             return None;
         }
 
+        // at most three occurrences are pointed at, the last one carries
+        // the suggestion
         let mut iter = occurrences.into_iter();
 
         let (first_annotation, first_subkey) = iter.next().unwrap();
         let first_message = format!("The key `{first_component_ident}` is first assigned here ...");
+        let mut report = self.report().diagnostic(first_annotation, first_message);
+
+        let (mut last_annotation, second_subkey) = iter.next().unwrap();
+        let mut subkeys = vec![first_subkey, second_subkey];
+        if let Some((third_annotation, third_subkey)) = iter.next() {
+            report = report.diagnostic(last_annotation, "... repeated here ...");
+            last_annotation = third_annotation;
+            subkeys.push(third_subkey);
+        }
 
-        let (second_annotation, second_subkey) = iter.next().unwrap();
-        let second_message = "... repeated here ...";
-
-        let (third_annotation, third_subkey) = iter.next().unwrap();
-        let third_message = {
+        let last_message = {
             let remaining_occurrences = iter.count();
             let mut message = match remaining_occurrences {
                 0 => "... and here.".to_string(),
                 1 => "... and here (`1` occurrence omitted).".to_string(),
                 n => format!("... and here (`{n}` occurrences omitted)."),
             };
-            write!(
-                message,
-                " Try `{first_component_ident} = {{ {first_subkey}=...; {second_subkey}=...; {third_subkey}=...; }}` instead."
-            )
-            .unwrap();
+            write!(message, " Try `{first_component_ident} = {{ ").unwrap();
+            for subkey in &subkeys {
+                write!(message, "{subkey}=...; ").unwrap();
+            }
+            message.push_str("}` instead.");
             message
         };
 
-        Some(
-            self.report()
-                .diagnostic(first_annotation, first_message)
-                .diagnostic(second_annotation, second_message)
-                .diagnostic(third_annotation, third_message),
-        )
+        Some(report.diagnostic(last_annotation, last_message))
     }
 }
@@ -1,10 +1,8 @@
-use crate::{Metadata, Report, Rule, Suggestion, make, session::SessionInfo};
+use crate::{Context, Metadata, Report, Rule, Suggestion, make, session::Version};
 
 use macros::lint;
-use rnix::{
-    NodeOrToken, SyntaxElement, SyntaxKind,
-    types::{Dynamic, TypedNode},
-};
+use rnix::{NodeOrToken, SyntaxElement, SyntaxKind, ast::Dynamic};
+use rowan::ast::AstNode as _;
 
 /// ## What it does
 /// Checks for antiquote/splice expressions that are not quoted.
@@ -13,6 +11,10 @@ use rnix::{
 /// An *anti*quoted expression should always occur within a *quoted*
 /// expression.
 ///
+/// This lint is only raised when targeting Nix versions before 2.4, set
+/// with `nix_version` in `statix.toml`, or detected from the `nix` found on
+/// `PATH`.
+///
 /// ## Example
 ///
 /// ```nix
@@ -38,17 +40,21 @@ use rnix::{
 )]
 struct UnquotedSplice;
 
+// the lint is only raised for versions of Nix before this one
+const QUOTES_OPTIONAL: Version = Version::new(2, 4, 0);
+
 impl Rule for UnquotedSplice {
-    fn validate(&self, node: &SyntaxElement, _sess: &SessionInfo) -> Option<Report> {
+    fn validate(&self, node: &SyntaxElement, ctx: &Context) -> Option<Report> {
         if let NodeOrToken::Node(node) = node
+            && !ctx.session().at_least(QUOTES_OPTIONAL)
             && Dynamic::cast(node.clone()).is_some()
         {
             let at = node.text_range();
-            let replacement = make::quote(node).node().clone();
+            let replacement = make::quote(node).syntax().clone();
             let message = "Consider quoting this splice expression";
             Some(
                 self.report()
-                    .suggest(at, message, Suggestion::new(at, replacement)),
+                    .suggest(at, message, Suggestion::with_replacement(at, replacement)),
             )
         } else {
             None
@@ -0,0 +1,99 @@
+use crate::{Context, Metadata, Report, Rule, Suggestion};
+
+use macros::lint;
+use rnix::{
+    NodeOrToken, SyntaxElement, SyntaxKind, TextRange,
+    ast::{PatEntry, Pattern},
+};
+use rowan::ast::AstNode as _;
+
+/// ## What it does
+/// Checks for formal arguments of a function pattern that are never used
+/// in the body of the function. Names starting with an underscore are
+/// ignored, as are patterns whose `@` binding is used, since the argument
+/// may be accessed through it.
+///
+/// ## Why is this bad?
+/// Unused arguments are noise, and make it harder to tell what a function
+/// depends on.
+///
+/// ## Example
+///
+/// ```nix
+/// { lib, pkgs, ... }: pkgs.hello
+/// ```
+///
+/// Remove the unused argument:
+///
+/// ```nix
+/// { pkgs, ... }: pkgs.hello
+/// ```
+///
+/// A fix is only suggested if the pattern contains `...`, otherwise
+/// removing the argument would break callers that pass it.
+#[lint(
+    name = "unused_lambda_argument",
+    note = "Unused function argument",
+    code = 26,
+    match_with = SyntaxKind::NODE_PAT_ENTRY
+)]
+struct UnusedLambdaArgument;
+
+impl Rule for UnusedLambdaArgument {
+    fn validate(&self, node: &SyntaxElement, ctx: &Context) -> Option<Report> {
+        let NodeOrToken::Node(node) = node else {
+            return None;
+        };
+
+        let pat_entry = PatEntry::cast(node.clone())?;
+        let pattern = Pattern::cast(node.parent()?)?;
+        let semantic = ctx.semantic();
+
+        let ident = pat_entry.ident()?;
+        let binding = semantic.binding_at(ident.syntax())?;
+        if binding.name.starts_with('_') || !binding.references.is_empty() {
+            return None;
+        }
+
+        // any argument may be used through the pattern bind
+        if let Some(pat_bind) = pattern.pat_bind().and_then(|b| b.ident())
+            && semantic
+                .binding_at(pat_bind.syntax())
+                .is_some_and(|b| !b.references.is_empty())
+        {
+            return None;
+        }
+
+        let at = ident.syntax().text_range();
+        let message = format!("Argument `{}` is never used", binding.name);
+
+        if pattern.ellipsis_token().is_none() {
+            return Some(self.report().diagnostic(at, message));
+        }
+
+        // remove the entry along with the comma and whitespace that follow
+        // it, the entry is never last since the pattern contains `...`
+        let end = std::iter::successors(node.next_sibling_or_token(), |el| {
+            el.next_sibling_or_token()
+        })
+        .find(|el| {
+            !matches!(
+                el.kind(),
+                SyntaxKind::TOKEN_COMMA | SyntaxKind::TOKEN_WHITESPACE
+            )
+        })?;
+        let replacement_at = TextRange::new(node.text_range().start(), end.text_range().start());
+
+        let has_comments = end.kind() == SyntaxKind::TOKEN_COMMENT
+            || node
+                .descendants_with_tokens()
+                .any(|el| el.kind() == SyntaxKind::TOKEN_COMMENT);
+
+        Some(if has_comments {
+            self.report().diagnostic(at, message)
+        } else {
+            self.report()
+                .suggest(at, message, Suggestion::with_empty(replacement_at))
+        })
+    }
+}
@@ -0,0 +1,196 @@
+use crate::{Context, Metadata, Report, Rule, Suggestion, TextEdit, semantic::Binding, utils};
+
+use macros::lint;
+use rnix::{
+    NodeOrToken, SyntaxElement, SyntaxKind, SyntaxNode, TextRange,
+    ast::{AttrpathValue, Inherit},
+};
+use rowan::{Direction, ast::AstNode as _};
+
+/// ## What it does
+/// Checks for bindings in `let-in` expressions, including those introduced
+/// by `inherit`, that are not referred to by the body of the expression or
+/// by any other binding. Names starting with an underscore are ignored.
+///
+/// ## Why is this bad?
+/// Unused bindings are dead code, usually left behind after a refactor.
+/// They make the expression harder to read.
+///
+/// ## Example
+///
+/// ```nix
+/// let
+///   inherit (pkgs) hello cowsay;
+///   greeting = "hi";
+/// in
+///   hello
+/// ```
+///
+/// Remove the unused bindings:
+///
+/// ```nix
+/// let
+///   inherit (pkgs) hello;
+/// in
+///   hello
+/// ```
+#[lint(
+    name = "unused_let_binding",
+    note = "Unused let binding",
+    code = 25,
+    match_with = [
+        SyntaxKind::NODE_ATTRPATH_VALUE,
+        SyntaxKind::NODE_INHERIT
+    ]
+)]
+struct UnusedLetBinding;
+
+impl Rule for UnusedLetBinding {
+    fn validate(&self, node: &SyntaxElement, ctx: &Context) -> Option<Report> {
+        let NodeOrToken::Node(node) = node else {
+            return None;
+        };
+        let let_in = node.parent()?;
+        if let_in.kind() != SyntaxKind::NODE_LET_IN {
+            return None;
+        }
+
+        if let Some(attrpath_value) = AttrpathValue::cast(node.clone()) {
+            self.validate_attrpath_value(node, &attrpath_value, &let_in, ctx)
+        } else {
+            self.validate_inherit(node, &Inherit::cast(node.clone())?, &let_in, ctx)
+        }
+    }
+}
+
+impl UnusedLetBinding {
+    fn validate_attrpath_value(
+        &self,
+        node: &SyntaxNode,
+        attrpath_value: &AttrpathValue,
+        let_in: &SyntaxNode,
+        ctx: &Context,
+    ) -> Option<Report> {
+        let attr = attrpath_value.attrpath()?.attrs().next()?;
+        let binding = ctx.semantic().binding_at(attr.syntax())?;
+        if !is_unused(binding, let_in) {
+            return None;
+        }
+
+        let at = attr.syntax().text_range();
+        let message = format!("`{}` is bound but never used", binding.name);
+        Some(if has_comments(node) {
+            self.report().diagnostic(at, message)
+        } else {
+            let replacement_at = utils::with_preceeding_whitespace(node);
+            self.report()
+                .suggest(at, message, Suggestion::with_empty(replacement_at))
+        })
+    }
+
+    fn validate_inherit(
+        &self,
+        node: &SyntaxNode,
+        inherit: &Inherit,
+        let_in: &SyntaxNode,
+        ctx: &Context,
+    ) -> Option<Report> {
+        let (unused, used): (Vec<_>, Vec<_>) = inherit.attrs().partition(|attr| {
+            ctx.semantic()
+                .binding_at(attr.syntax())
+                .is_some_and(|binding| is_unused(binding, let_in))
+        });
+        if unused.is_empty() {
+            return None;
+        }
+
+        // remove the unused names one by one, or drop the statement
+        // entirely if nothing remains
+        let suggestion = if has_comments(node) {
+            None
+        } else if used.is_empty() {
+            let replacement_at = utils::with_preceeding_whitespace(node);
+            Some(Suggestion::with_empty(replacement_at))
+        } else {
+            let edits = unused
+                .iter()
+                .map(|attr| TextEdit::with_empty(utils::with_preceeding_whitespace(attr.syntax())))
+                .collect();
+            Suggestion::with_edits(edits)
+        };
+
+        let mut report = self.report();
+        let mut suggestion = suggestion;
+        for attr in unused {
+            let at = attr.syntax().text_range();
+            let message = format!("`{}` is inherited but never used", attr.syntax());
+            report = match suggestion.take() {
+                Some(suggestion) => report.suggest(at, message, suggestion),
+                None => report.diagnostic(at, message),
+            };
+        }
+        Some(report)
+    }
+}
+
+// a binding is unused if it has no references outside of the entries that
+// define it, recursive references do not count
+fn is_unused(binding: &Binding, let_in: &SyntaxNode) -> bool {
+    if binding.name.starts_with('_') {
+        return false;
+    }
+    let entries = binding
+        .definitions
+        .iter()
+        .filter_map(|at| defining_entry(let_in, *at))
+        .collect::<Vec<_>>();
+    binding
+        .references
+        .iter()
+        .all(|reference| entries.iter().any(|entry| entry.contains_range(*reference)))
+}
+
+fn defining_entry(let_in: &SyntaxNode, at: TextRange) -> Option<TextRange> {
+    let_in
+        .covering_element(at)
+        .ancestors()
+        .find(|node| {
+            matches!(
+                node.kind(),
+                SyntaxKind::NODE_ATTRPATH_VALUE | SyntaxKind::NODE_INHERIT
+            )
+        })
+        .map(|node| node.text_range())
+}
+
+// comments within the binding, on the lines above it, or after it on the
+// same line would be removed along with it, or left describing something else
+fn has_comments(node: &SyntaxNode) -> bool {
+    let is_trivia = |el: &SyntaxElement| {
+        matches!(
+            el.kind(),
+            SyntaxKind::TOKEN_WHITESPACE | SyntaxKind::TOKEN_COMMENT
+        )
+    };
+    let leading = node
+        .siblings_with_tokens(Direction::Prev)
+        .skip(1)
+        .take_while(is_trivia)
+        .filter(|el| el.kind() == SyntaxKind::TOKEN_COMMENT)
+        // a comment after the previous entry on its line belongs to that entry
+        .any(|comment| {
+            comment
+                .prev_sibling_or_token()
+                .is_none_or(|el| is_trivia(&el) && el.to_string().contains('\n'))
+        });
+    let trailing = node
+        .siblings_with_tokens(Direction::Next)
+        .skip(1)
+        .take_while(|el| is_trivia(el) && !el.to_string().contains('\n'))
+        .any(|el| el.kind() == SyntaxKind::TOKEN_COMMENT);
+    leading
+        || trailing
+        || node
+            .descendants_with_tokens()
+            .any(|el| el.kind() == SyntaxKind::TOKEN_COMMENT)
+}
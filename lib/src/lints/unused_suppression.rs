@@ -0,0 +1,77 @@
+use crate::{
+    Context, LINTS, Metadata, Report, Rule,
+    suppression::{Directive, parse_directive},
+};
+
+use macros::lint;
+use rnix::{NodeOrToken, SyntaxElement, SyntaxKind};
+
+/// ## What it does
+/// Checks for `# statix: allow(..)` and `# statix: allow-file(..)`
+/// comments that do not suppress anything, either because they name
+/// an unknown lint, or because the named lint is not raised in the
+/// region they cover.
+///
+/// ## Why is this bad?
+/// Stale suppressions hide future occurrences of a lint and mislead
+/// readers into thinking the code below them is problematic.
+///
+/// ## Example
+///
+/// ```nix
+/// {
+///   # statix: allow(manual_inherit)
+///   a = b;
+/// }
+/// ```
+///
+/// Remove the comment:
+///
+/// ```nix
+/// {
+///   a = b;
+/// }
+/// ```
+#[lint(
+    name = "unused_suppression",
+    note = "Suppression comment has no effect",
+    code = 24,
+    match_with = SyntaxKind::TOKEN_COMMENT
+)]
+struct UnusedSuppression;
+
+impl Rule for UnusedSuppression {
+    fn validate(&self, node: &SyntaxElement, _ctx: &Context) -> Option<Report> {
+        let NodeOrToken::Token(token) = node else {
+            return None;
+        };
+
+        let at = token.text_range();
+        let names = match parse_directive(token.text())? {
+            Directive::Allow(names) | Directive::AllowFile(names) => names,
+            Directive::Malformed => {
+                return Some(self.report().diagnostic(
+                    at,
+                    "Malformed suppression, expected `statix: allow(<lint>, ..)` or `statix: allow-file(<lint>, ..)`",
+                ));
+            }
+        };
+
+        names
+            .into_iter()
+            .filter(|(name, _)| !LINTS.iter().any(|l| l.name() == *name))
+            .map(|(name, name_at)| {
+                (
+                    name_at + at.start(),
+                    format!("Unknown lint `{name}`, see `statix list` for available lints"),
+                )
+            })
+            .fold(None, |report: Option<Report>, (name_at, message)| {
+                Some(
+                    report
+                        .unwrap_or_else(|| self.report())
+                        .diagnostic(name_at, message),
+                )
+            })
+    }
+}
@@ -1,8 +1,8 @@
-use crate::{Diagnostic, Metadata, Report, Rule, Suggestion};
+use crate::{Context, Diagnostic, Metadata, Report, Rule, Suggestion, make};
 
 use macros::lint;
 use rnix::{
-    NodeOrToken, SyntaxElement, SyntaxKind,
+    NodeOrToken, SyntaxElement, SyntaxKind, SyntaxNode, TextRange,
     ast::{AttrpathValue, Expr, LetIn, Paren},
 };
 use rowan::ast::AstNode as _;
@@ -32,6 +32,13 @@ use rowan::ast::AstNode as _;
 /// in
 ///   2 + 3
 /// ```
+///
+/// ## Options
+/// - `bindings` (default `true`): parentheses around the value of a binding
+/// - `let_body` (default `true`): parentheses around the body of a `let`
+///   expression
+/// - `primitives` (default `true`): parentheses around lists, strings,
+///   attribute sets, identifiers and selections
 #[lint(
     name = "useless_parens",
     note = "These parentheses can be omitted",
@@ -40,44 +47,49 @@ use rowan::ast::AstNode as _;
         SyntaxKind::NODE_ATTRPATH_VALUE,
         SyntaxKind::NODE_PAREN,
         SyntaxKind::NODE_LET_IN,
-    ]
+    ],
+    options = [bindings = true, let_body = true, primitives = true]
 )]
 struct UselessParens;
 
 impl Rule for UselessParens {
-    fn validate(&self, node: &SyntaxElement) -> Option<Report> {
+    fn validate(&self, node: &SyntaxElement, ctx: &Context) -> Option<Report> {
         let NodeOrToken::Node(node) = node else {
             return None;
         };
 
+        let enabled = |option| ctx.session().option::<bool>(self, option);
         let diagnostic = match (AttrpathValue::cast(node.clone()), Expr::cast(node.clone())) {
             (Some(attrpath_value), _) => {
+                if !enabled("bindings") {
+                    return None;
+                }
                 let value_node = attrpath_value.value()?;
                 let value_range = value_node.syntax().text_range();
                 let paren = Paren::cast(value_node.syntax().clone())?;
-                let suggestion =
-                    Suggestion::with_replacement(value_range, paren.expr()?.syntax().clone());
-
-                Diagnostic::suggest(
+                diagnostic(
                     value_range,
                     "Useless parentheses around value in binding",
-                    suggestion,
+                    &paren,
                 )
             }
             (_, Some(Expr::LetIn(let_in))) => {
+                if !enabled("let_body") {
+                    return None;
+                }
                 let body_node = let_in.body()?;
                 let body_range = body_node.syntax().text_range();
                 let paren = Paren::cast(body_node.syntax().clone())?;
-                let suggestion =
-                    Suggestion::with_replacement(body_range, paren.expr()?.syntax().clone());
-
-                Diagnostic::suggest(
+                diagnostic(
                     body_range,
                     "Useless parentheses around body of `let` expression",
-                    suggestion,
+                    &paren,
                 )
             }
             (_, Some(Expr::Paren(paren_expr))) => {
+                if !enabled("primitives") {
+                    return None;
+                }
                 let paren_expr_range = paren_expr.syntax().text_range();
                 let father_node = paren_expr.syntax().parent()?;
 
@@ -105,10 +117,10 @@ impl Rule for UselessParens {
                     _ => return None,
                 }
 
-                Diagnostic::suggest(
+                diagnostic(
                     paren_expr_range,
                     "Useless parentheses around primitive expression",
-                    Suggestion::with_replacement(paren_expr_range, parsed_inner.syntax().clone()),
+                    &paren_expr,
                 )
             }
             _ => return None,
@@ -119,3 +131,27 @@ impl Rule for UselessParens {
         Some(report)
     }
 }
+
+// the fix is left out if the parentheses cannot be unwrapped
+fn diagnostic(at: TextRange, message: &str, paren: &Paren) -> Diagnostic {
+    match unwrap(paren) {
+        Some(inner) => Diagnostic::suggest(at, message, Suggestion::with_replacement(at, inner)),
+        None => Diagnostic::new(at, message),
+    }
+}
+
+// the inner expression, comments within the parentheses are kept around it
+fn unwrap(paren: &Paren) -> Option<SyntaxNode> {
+    let inner = paren.expr()?;
+    let inner_range = inner.syntax().text_range();
+    let (before, after): (Vec<_>, Vec<_>) = paren
+        .syntax()
+        .children_with_tokens()
+        .filter(|el| el.as_token().is_some())
+        .partition(|el| el.text_range().end() <= inner_range.start());
+    make::Builder::new(paren.syntax())
+        .comments(before)
+        .element(inner.syntax().clone())
+        .comments(after)
+        .build_expr()
+}
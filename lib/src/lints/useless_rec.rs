@@ -0,0 +1,75 @@
+use crate::{Context, Metadata, Report, Rule, Suggestion};
+
+use macros::lint;
+use rnix::{NodeOrToken, SyntaxElement, SyntaxKind, TextRange, ast::AttrSet};
+use rowan::ast::AstNode as _;
+
+/// ## What it does
+/// Checks for recursive attribute sets where no attribute refers to
+/// another attribute of the same set.
+///
+/// ## Why is this bad?
+/// `rec` makes readers look for references between attributes, and
+/// allows attributes to accidentally shadow names from an outer scope.
+///
+/// ## Example
+///
+/// ```nix
+/// rec {
+///   name = "statix";
+///   version = "0.5.8";
+/// }
+/// ```
+///
+/// Remove the `rec` keyword:
+///
+/// ```nix
+/// {
+///   name = "statix";
+///   version = "0.5.8";
+/// }
+/// ```
+#[lint(
+    name = "useless_rec",
+    note = "Useless rec attribute set",
+    code = 27,
+    match_with = SyntaxKind::NODE_ATTR_SET
+)]
+struct UselessRec;
+
+impl Rule for UselessRec {
+    fn validate(&self, node: &SyntaxElement, ctx: &Context) -> Option<Report> {
+        let NodeOrToken::Node(node) = node else {
+            return None;
+        };
+
+        let rec_token = AttrSet::cast(node.clone())?.rec_token()?;
+        let semantic = ctx.semantic();
+        let scope = semantic.scope_at(node)?;
+        if semantic
+            .bindings(scope.id)
+            .any(|binding| !binding.references.is_empty())
+        {
+            return None;
+        }
+
+        // remove `rec` along with the whitespace that follows it
+        let next = rec_token.next_sibling_or_token()?;
+        let end = if next.kind() == SyntaxKind::TOKEN_WHITESPACE {
+            next.next_sibling_or_token()?
+        } else {
+            next
+        };
+
+        let at = rec_token.text_range();
+        let message =
+            "None of the attributes in this set refer to each other, `rec` is unnecessary";
+        Some(if end.kind() == SyntaxKind::TOKEN_COMMENT {
+            self.report().diagnostic(at, message)
+        } else {
+            let replacement_at = TextRange::new(at.start(), end.text_range().start());
+            self.report()
+                .suggest(at, message, Suggestion::with_empty(replacement_at))
+        })
+    }
+}
@@ -1,7 +1,7 @@
-use std::{fmt::Write, iter::IntoIterator};
+use std::iter::IntoIterator;
 
 use rnix::{
-    Root, SyntaxNode,
+    NodeOrToken, Root, SyntaxElement, SyntaxKind, SyntaxNode,
     ast::{self, AstNode},
 };
 use rowan::ast::AstNode as _;
@@ -36,46 +36,6 @@ pub fn unary_not(node: &SyntaxNode) -> ast::UnaryOp {
     ast_from_text(&format!("!{node}"))
 }
 
-pub fn inherit_stmt<'a>(nodes: impl IntoIterator<Item = &'a ast::Ident>) -> ast::Inherit {
-    let inherited_idents = nodes
-        .into_iter()
-        .map(std::string::ToString::to_string)
-        .collect::<Vec<_>>()
-        .join(" ");
-    ast_from_text(&format!("{{ inherit {inherited_idents}; }}"))
-}
-
-pub fn inherit_from_stmt<'a>(
-    from: &SyntaxNode,
-    nodes: impl IntoIterator<Item = &'a ast::Ident>,
-) -> ast::Inherit {
-    let inherited_idents = nodes
-        .into_iter()
-        .map(std::string::ToString::to_string)
-        .collect::<Vec<_>>()
-        .join(" ");
-    ast_from_text(&format!("{{ inherit ({from}) {inherited_idents}; }}"))
-}
-
-pub fn attrset(
-    inherits: impl IntoIterator<Item = ast::Inherit>,
-    entries: impl IntoIterator<Item = ast::Entry>,
-    recursive: bool,
-) -> ast::AttrSet {
-    let mut buffer = String::new();
-
-    writeln!(buffer, "{}{{", if recursive { "rec " } else { "" }).unwrap();
-    for inherit in inherits {
-        writeln!(buffer, "  {inherit}").unwrap();
-    }
-    for entry in entries {
-        writeln!(buffer, "  {entry}").unwrap();
-    }
-    write!(buffer, "}}").unwrap();
-
-    ast_from_text(&buffer)
-}
-
 pub fn select(set: &SyntaxNode, index: &SyntaxNode) -> ast::Select {
     ast_from_text(&format!("{set}.{index}"))
 }
@@ -92,3 +52,196 @@ pub fn binary(lhs: &SyntaxNode, op: &str, rhs: &SyntaxNode) -> ast::BinOp {
 pub fn or_default(set: &SyntaxNode, index: &SyntaxNode, default: &SyntaxNode) -> ast::Select {
     ast_from_text(&format!("{set}.{index} or {default}"))
 }
+
+/// Builds a fix out of new code and pieces of the original tree. Pieces
+/// keep their comments and whitespace, and are re-indented to match the
+/// place the fix goes to, so that fixed files need not be reformatted.
+pub struct Builder {
+    text: String,
+    indent: String,
+    after: After,
+}
+
+// what the fix ends with, code cannot follow a line comment on its line
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum After {
+    Code,
+    BlockComment,
+    LineComment,
+}
+
+impl Builder {
+    /// Start a fix that replaces `node`
+    pub fn new(node: &SyntaxNode) -> Self {
+        Self {
+            text: String::new(),
+            indent: indent_of(node),
+            after: After::Code,
+        }
+    }
+
+    /// Append new code
+    #[must_use]
+    pub fn text(mut self, text: &str) -> Self {
+        self.push(text);
+        self
+    }
+
+    /// Start a new line, indented `extra` further than the fix
+    #[must_use]
+    pub fn line(mut self, extra: &str) -> Self {
+        self.text.push('\n');
+        self.text.push_str(&self.indent);
+        self.text.push_str(extra);
+        self.after = After::Code;
+        self
+    }
+
+    /// Append a piece of the original tree. Lines after the first are
+    /// re-indented from the line its parent starts on, to the line of the
+    /// fix.
+    #[must_use]
+    pub fn element(mut self, element: impl Into<SyntaxElement>) -> Self {
+        let element = element.into();
+        let text = element.to_string();
+        let from = element.parent().map(|p| indent_of(&p)).unwrap_or_default();
+        if from == self.indent {
+            self.push(&text);
+        } else {
+            self.push(&reindent(&text, &from, &self.indent));
+        }
+        if element.kind() == SyntaxKind::TOKEN_COMMENT {
+            self.after = comment_kind(&text);
+        }
+        self
+    }
+
+    /// Append several pieces of the original tree, see `element`
+    #[must_use]
+    pub fn elements(self, elements: impl IntoIterator<Item = SyntaxElement>) -> Self {
+        elements.into_iter().fold(self, Self::element)
+    }
+
+    /// Append the comments found in `elements`, code that follows is kept
+    /// off of the line of a line comment
+    #[must_use]
+    pub fn comments(self, elements: impl IntoIterator<Item = SyntaxElement>) -> Self {
+        comments(elements).fold(self, |mut b, comment| {
+            if !b.text.is_empty() && !b.text.ends_with(char::is_whitespace) {
+                b.push(" ");
+            }
+            b.push(&comment);
+            b.after = comment_kind(&comment);
+            b
+        })
+    }
+
+    /// Parse the fix, the first `N` node in it is returned
+    pub fn build<N: AstNode>(self) -> N {
+        ast_from_text(&self.finish())
+    }
+
+    /// Parse the fix as a whole, along with comments around the
+    /// expression in it. Returns `None` if the fix does not parse.
+    pub fn build_expr(self) -> Option<SyntaxNode> {
+        let parse = Root::parse(&self.finish()).ok().ok()?;
+        Some(parse.syntax().clone())
+    }
+
+    // code that follows the fix cannot go on the line of a line comment
+    fn finish(mut self) -> String {
+        if self.after == After::LineComment {
+            self = self.line("");
+        }
+        self.text
+    }
+
+    fn push(&mut self, text: &str) {
+        let trimmed = text.trim_start_matches([' ', '\t']);
+        match self.after {
+            _ if text.is_empty() => return,
+            After::LineComment if trimmed.is_empty() => return,
+            After::LineComment if !trimmed.starts_with('\n') => {
+                // the first line of the fix is indented by the source
+                let indent = match self.text.rfind('\n') {
+                    Some(i) => leading_whitespace(&self.text[i + 1..]),
+                    None => self.indent.clone(),
+                };
+                self.text.push('\n');
+                self.text.push_str(&indent);
+                self.text.push_str(trimmed);
+                self.after = After::Code;
+                return;
+            }
+            After::BlockComment if !text.starts_with(char::is_whitespace) => {
+                self.text.push(' ');
+            }
+            _ => (),
+        }
+        self.text.push_str(text);
+        self.after = After::Code;
+    }
+}
+
+fn comment_kind(comment: &str) -> After {
+    if comment.starts_with('#') {
+        After::LineComment
+    } else {
+        After::BlockComment
+    }
+}
+
+/// Indentation of the line `node` starts on, read off of the tokens that
+/// precede it on that line
+pub fn indent_of(node: &SyntaxNode) -> String {
+    let mut line = Vec::new();
+    let mut token = node.first_token().and_then(|t| t.prev_token());
+    while let Some(t) = token {
+        if let Some((_, rest)) = t.text().rsplit_once('\n') {
+            line.push(rest.to_owned());
+            break;
+        }
+        line.push(t.text().to_owned());
+        token = t.prev_token();
+    }
+    line.reverse();
+    leading_whitespace(&line.concat())
+}
+
+fn leading_whitespace(line: &str) -> String {
+    line.chars()
+        .take_while(|c| matches!(c, ' ' | '\t'))
+        .collect()
+}
+
+/// Comments among `elements` and their descendants
+pub fn comments(elements: impl IntoIterator<Item = SyntaxElement>) -> impl Iterator<Item = String> {
+    elements
+        .into_iter()
+        .flat_map(|el| match el {
+            NodeOrToken::Node(node) => node
+                .descendants_with_tokens()
+                .filter_map(NodeOrToken::into_token)
+                .collect(),
+            NodeOrToken::Token(token) => vec![token],
+        })
+        .filter(|token| token.kind() == SyntaxKind::TOKEN_COMMENT)
+        .map(|token| token.text().to_owned())
+}
+
+// lines that are indented less than `from` are left as is
+fn reindent(text: &str, from: &str, to: &str) -> String {
+    let mut lines = text.split('\n');
+    let mut out = lines.next().unwrap_or_default().to_owned();
+    for line in lines {
+        out.push('\n');
+        match line.strip_prefix(from) {
+            Some(rest) => {
+                out.push_str(to);
+                out.push_str(rest);
+            }
+            None => out.push_str(line),
+        }
+    }
+    out
+}
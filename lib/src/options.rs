@@ -0,0 +1,74 @@
+//! Options of individual lints. Lints declare their options along with
+//! defaults in the `lint` attribute macro, configured values are carried by
+//! the [`SessionInfo`](crate::session::SessionInfo).
+
+use std::{collections::HashMap, fmt};
+
+/// Value of a lint option, the type of an option is that of its default
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptionValue {
+    Bool(bool),
+    Int(u64),
+}
+
+impl OptionValue {
+    /// Name of the type of the value, as written in `statix.toml`
+    #[must_use]
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::Bool(_) => "boolean",
+            Self::Int(_) => "non-negative integer",
+        }
+    }
+}
+
+impl fmt::Display for OptionValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bool(b) => write!(f, "{b}"),
+            Self::Int(i) => write!(f, "{i}"),
+        }
+    }
+}
+
+/// An option declared by a lint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LintOption {
+    pub name: &'static str,
+    pub default: OptionValue,
+    /// Smallest value accepted for an integer option
+    pub min: Option<u64>,
+}
+
+/// Configured option values, keyed by lint name and then option name.
+/// Options that are not present take their default.
+pub type LintOptions = HashMap<&'static str, HashMap<&'static str, OptionValue>>;
+
+/// Types that lint options are read as
+pub trait FromOptionValue: Sized {
+    fn from_option_value(value: OptionValue) -> Option<Self>;
+}
+
+impl FromOptionValue for bool {
+    fn from_option_value(value: OptionValue) -> Option<Self> {
+        match value {
+            OptionValue::Bool(b) => Some(b),
+            OptionValue::Int(_) => None,
+        }
+    }
+}
+
+impl FromOptionValue for u64 {
+    fn from_option_value(value: OptionValue) -> Option<Self> {
+        match value {
+            OptionValue::Int(i) => Some(i),
+            OptionValue::Bool(_) => None,
+        }
+    }
+}
+
+impl FromOptionValue for usize {
+    fn from_option_value(value: OptionValue) -> Option<Self> {
+        u64::from_option_value(value).and_then(|i| i.try_into().ok())
+    }
+}
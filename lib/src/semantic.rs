@@ -0,0 +1,537 @@
+use std::collections::HashMap;
+
+use rnix::{
+    SyntaxKind, SyntaxNode, TextRange,
+    ast::{self, Attr, Entry, HasEntry as _, InterpolPart, Param},
+};
+use rowan::ast::{AstChildren, AstNode as _};
+
+/// Names that are in scope in every nix expression, without a `builtins.`
+/// prefix. Names starting with `__` are also treated as builtins.
+const BUILTINS: &[&str] = &[
+    "abort",
+    "baseNameOf",
+    "break",
+    "builtins",
+    "derivation",
+    "derivationStrict",
+    "dirOf",
+    "false",
+    "fetchGit",
+    "fetchMercurial",
+    "fetchTarball",
+    "fetchTree",
+    "fromTOML",
+    "import",
+    "isNull",
+    "map",
+    "null",
+    "placeholder",
+    "removeAttrs",
+    "scopedImport",
+    "throw",
+    "toString",
+    "true",
+];
+
+fn is_builtin(name: &str) -> bool {
+    name.starts_with("__") || BUILTINS.contains(&name)
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ScopeId(usize);
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BindingId(usize);
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ScopeKind {
+    /// The outermost scope, contains builtins
+    Root,
+    /// `let .. in ..` or the legacy `let { .. }`
+    Let,
+    /// `rec { .. }`
+    RecAttrSet,
+    /// Parameters of a function
+    Lambda,
+    /// `with expr; ..`, attributes of `expr` are in scope, but do not
+    /// shadow lexical bindings
+    With,
+}
+
+#[derive(Debug)]
+pub struct Scope {
+    pub id: ScopeId,
+    pub kind: ScopeKind,
+    pub parent: Option<ScopeId>,
+    /// Range of the node that introduces this scope
+    pub range: TextRange,
+    /// Bindings introduced by this scope, in order of definition
+    pub bindings: Vec<BindingId>,
+    names: HashMap<String, BindingId>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BindingKind {
+    /// A builtin such as `map` or `true`
+    Builtin,
+    /// `name = value;` within a `let` or `rec` attrset
+    Attr,
+    /// `inherit name;` or `inherit (from) name;` within a `let` or `rec`
+    /// attrset
+    Inherit,
+    /// `name: body`
+    Param,
+    /// `{ name ? default }: body`
+    Formal,
+    /// `{ .. }@name: body`
+    PatternBind,
+}
+
+#[derive(Debug)]
+pub struct Binding {
+    pub id: BindingId,
+    pub name: String,
+    pub kind: BindingKind,
+    pub scope: ScopeId,
+    /// Identifiers that define this binding. A binding may be defined in
+    /// parts, for example, `let a.b = 1; a.c = 2; in a`. Builtins have no
+    /// definitions.
+    pub definitions: Vec<TextRange>,
+    /// Identifiers that refer to this binding
+    pub references: Vec<TextRange>,
+}
+
+/// What an identifier refers to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Resolution {
+    /// A lexical binding or a builtin
+    Binding(BindingId),
+    /// No lexical binding exists, the identifier may be provided by any of
+    /// these `with` scopes, innermost first
+    With(Vec<ScopeId>),
+    /// The identifier is not bound
+    Unresolved,
+}
+
+/// Scopes, bindings and references of a file, built once and shared by
+/// all rules via `Context`.
+#[derive(Debug, Default)]
+pub struct SemanticModel {
+    scopes: Vec<Scope>,
+    bindings: Vec<Binding>,
+    definitions: HashMap<TextRange, BindingId>,
+    references: HashMap<TextRange, Resolution>,
+    scope_nodes: HashMap<TextRange, ScopeId>,
+}
+
+impl SemanticModel {
+    #[must_use]
+    pub fn new(root: &SyntaxNode) -> Self {
+        let mut model = Self::default();
+        let root_scope = model.push_scope(ScopeKind::Root, None, root.text_range());
+        model.walk(root, root_scope);
+        model
+    }
+
+    /// What the identifier `ident` refers to, `None` if `ident` is not a
+    /// reference
+    #[must_use]
+    pub fn resolve(&self, ident: &SyntaxNode) -> Option<&Resolution> {
+        self.references.get(&ident.text_range())
+    }
+
+    /// The binding that the identifier `ident` refers to, `None` if it is
+    /// not lexically bound
+    #[must_use]
+    pub fn definition(&self, ident: &SyntaxNode) -> Option<&Binding> {
+        match self.resolve(ident)? {
+            Resolution::Binding(id) => Some(self.binding(*id)),
+            _ => None,
+        }
+    }
+
+    /// The binding defined by the identifier `ident`, for example, `a` in
+    /// `let a = 1; in ..`
+    #[must_use]
+    pub fn binding_at(&self, ident: &SyntaxNode) -> Option<&Binding> {
+        self.definitions
+            .get(&ident.text_range())
+            .map(|id| self.binding(*id))
+    }
+
+    #[must_use]
+    pub fn binding(&self, id: BindingId) -> &Binding {
+        &self.bindings[id.0]
+    }
+
+    /// The scope introduced by `node`, if any
+    #[must_use]
+    pub fn scope_at(&self, node: &SyntaxNode) -> Option<&Scope> {
+        self.scope_nodes
+            .get(&node.text_range())
+            .map(|id| self.scope(*id))
+    }
+
+    #[must_use]
+    pub fn scope(&self, id: ScopeId) -> &Scope {
+        &self.scopes[id.0]
+    }
+
+    /// Bindings introduced by the scope `id`
+    pub fn bindings(&self, id: ScopeId) -> impl Iterator<Item = &Binding> {
+        self.scope(id).bindings.iter().map(|b| self.binding(*b))
+    }
+
+    fn push_scope(
+        &mut self,
+        kind: ScopeKind,
+        parent: Option<ScopeId>,
+        range: TextRange,
+    ) -> ScopeId {
+        let id = ScopeId(self.scopes.len());
+        self.scopes.push(Scope {
+            id,
+            kind,
+            parent,
+            range,
+            bindings: Vec::new(),
+            names: HashMap::new(),
+        });
+        if parent.is_some() {
+            self.scope_nodes.insert(range, id);
+        }
+        id
+    }
+
+    fn define(&mut self, name: String, kind: BindingKind, scope: ScopeId) -> BindingId {
+        if let Some(id) = self.scopes[scope.0].names.get(&name) {
+            return *id;
+        }
+        let id = BindingId(self.bindings.len());
+        self.scopes[scope.0].names.insert(name.clone(), id);
+        self.scopes[scope.0].bindings.push(id);
+        self.bindings.push(Binding {
+            id,
+            name,
+            kind,
+            scope,
+            definitions: Vec::new(),
+            references: Vec::new(),
+        });
+        id
+    }
+
+    fn define_at(&mut self, at: TextRange, name: String, kind: BindingKind, scope: ScopeId) {
+        let id = self.define(name, kind, scope);
+        self.bindings[id.0].definitions.push(at);
+        self.definitions.insert(at, id);
+    }
+
+    fn define_entries(&mut self, entries: AstChildren<Entry>, scope: ScopeId) {
+        for entry in entries {
+            match entry {
+                Entry::AttrpathValue(attrpath_value) => {
+                    if let Some(attr) = attrpath_value
+                        .attrpath()
+                        .and_then(|attrpath| attrpath.attrs().next())
+                        && let Some(name) = static_name(&attr)
+                    {
+                        self.define_at(attr.syntax().text_range(), name, BindingKind::Attr, scope);
+                    }
+                }
+                Entry::Inherit(inherit) => {
+                    for attr in inherit.attrs() {
+                        if let Some(name) = static_name(&attr) {
+                            let at = attr.syntax().text_range();
+                            self.define_at(at, name, BindingKind::Inherit, scope);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn define_params(&mut self, lambda: &ast::Lambda, scope: ScopeId) {
+        match lambda.param() {
+            Some(Param::IdentParam(param)) => {
+                if let Some(ident) = param.ident() {
+                    let at = ident.syntax().text_range();
+                    self.define_at(at, ident.to_string(), BindingKind::Param, scope);
+                }
+            }
+            Some(Param::Pattern(pattern)) => {
+                for ident in pattern.pat_entries().filter_map(|e| e.ident()) {
+                    let at = ident.syntax().text_range();
+                    self.define_at(at, ident.to_string(), BindingKind::Formal, scope);
+                }
+                if let Some(ident) = pattern.pat_bind().and_then(|b| b.ident()) {
+                    let at = ident.syntax().text_range();
+                    self.define_at(at, ident.to_string(), BindingKind::PatternBind, scope);
+                }
+            }
+            None => (),
+        }
+    }
+
+    fn lookup(&mut self, name: &str, scope: ScopeId) -> Resolution {
+        let mut with_scopes = Vec::new();
+        let mut current = Some(scope);
+        while let Some(id) = current {
+            let scope = &self.scopes[id.0];
+            if let Some(binding) = scope.names.get(name) {
+                return Resolution::Binding(*binding);
+            }
+            match scope.kind {
+                ScopeKind::With => with_scopes.push(id),
+                ScopeKind::Root if is_builtin(name) => {
+                    return Resolution::Binding(self.define(
+                        name.to_owned(),
+                        BindingKind::Builtin,
+                        id,
+                    ));
+                }
+                _ => (),
+            }
+            current = scope.parent;
+        }
+        if with_scopes.is_empty() {
+            Resolution::Unresolved
+        } else {
+            Resolution::With(with_scopes)
+        }
+    }
+
+    fn reference(&mut self, at: TextRange, name: &str, scope: ScopeId) {
+        let resolution = self.lookup(name, scope);
+        if let Resolution::Binding(id) = resolution {
+            self.bindings[id.0].references.push(at);
+        }
+        self.references.insert(at, resolution);
+    }
+
+    fn walk_children(&mut self, node: &SyntaxNode, scope: ScopeId, inherit_scope: ScopeId) {
+        for child in node.children() {
+            if child.kind() == SyntaxKind::NODE_INHERIT {
+                self.walk_inherit(&child, scope, inherit_scope);
+            } else {
+                self.walk(&child, scope);
+            }
+        }
+    }
+
+    // names in `inherit a b;` are looked up in `inherit_scope`, the
+    // expression in `inherit (expr) a b;` is looked up in `scope`
+    fn walk_inherit(&mut self, node: &SyntaxNode, scope: ScopeId, inherit_scope: ScopeId) {
+        let Some(inherit) = ast::Inherit::cast(node.clone()) else {
+            return;
+        };
+        if let Some(from) = inherit.from() {
+            self.walk(from.syntax(), scope);
+            return;
+        }
+        for attr in inherit.attrs() {
+            if let Some(name) = static_name(&attr) {
+                self.reference(attr.syntax().text_range(), &name, inherit_scope);
+            }
+        }
+    }
+
+    fn walk(&mut self, node: &SyntaxNode, scope: ScopeId) {
+        match node.kind() {
+            SyntaxKind::NODE_LET_IN => {
+                let Some(let_in) = ast::LetIn::cast(node.clone()) else {
+                    return;
+                };
+                let inner = self.push_scope(ScopeKind::Let, Some(scope), node.text_range());
+                self.define_entries(let_in.entries(), inner);
+                self.walk_children(node, inner, scope);
+            }
+            SyntaxKind::NODE_LEGACY_LET => {
+                let Some(legacy_let) = ast::LegacyLet::cast(node.clone()) else {
+                    return;
+                };
+                let inner = self.push_scope(ScopeKind::Let, Some(scope), node.text_range());
+                self.define_entries(legacy_let.entries(), inner);
+                self.walk_children(node, inner, scope);
+            }
+            SyntaxKind::NODE_ATTR_SET => {
+                let Some(attr_set) = ast::AttrSet::cast(node.clone()) else {
+                    return;
+                };
+                if attr_set.rec_token().is_some() {
+                    let inner =
+                        self.push_scope(ScopeKind::RecAttrSet, Some(scope), node.text_range());
+                    self.define_entries(attr_set.entries(), inner);
+                    self.walk_children(node, inner, scope);
+                } else {
+                    self.walk_children(node, scope, scope);
+                }
+            }
+            SyntaxKind::NODE_LAMBDA => {
+                let Some(lambda) = ast::Lambda::cast(node.clone()) else {
+                    return;
+                };
+                let inner = self.push_scope(ScopeKind::Lambda, Some(scope), node.text_range());
+                self.define_params(&lambda, inner);
+                self.walk_children(node, inner, inner);
+            }
+            SyntaxKind::NODE_WITH => {
+                let Some(with) = ast::With::cast(node.clone()) else {
+                    return;
+                };
+                if let Some(namespace) = with.namespace() {
+                    self.walk(namespace.syntax(), scope);
+                }
+                let inner = self.push_scope(ScopeKind::With, Some(scope), node.text_range());
+                if let Some(body) = with.body() {
+                    self.walk(body.syntax(), inner);
+                }
+            }
+            SyntaxKind::NODE_INHERIT => self.walk_inherit(node, scope, scope),
+            SyntaxKind::NODE_IDENT => {
+                if is_reference(node) {
+                    self.reference(node.text_range(), &node.text().to_string(), scope);
+                }
+            }
+            _ => self.walk_children(node, scope, scope),
+        }
+    }
+}
+
+// identifiers in attribute names and function parameters are not
+// references
+fn is_reference(ident: &SyntaxNode) -> bool {
+    let Some(parent) = ident.parent() else {
+        return true;
+    };
+    match parent.kind() {
+        SyntaxKind::NODE_ATTRPATH | SyntaxKind::NODE_IDENT_PARAM | SyntaxKind::NODE_PAT_BIND => {
+            false
+        }
+        SyntaxKind::NODE_PAT_ENTRY => ast::PatEntry::cast(parent)
+            .and_then(|entry| entry.ident())
+            .is_none_or(|name| name.syntax() != ident),
+        _ => true,
+    }
+}
+
+fn static_name(attr: &Attr) -> Option<String> {
+    match attr {
+        Attr::Ident(ident) => Some(ident.to_string()),
+        Attr::Str(s) => match s.normalized_parts().as_slice() {
+            [InterpolPart::Literal(name)] => Some(name.clone()),
+            _ => None,
+        },
+        Attr::Dynamic(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use rnix::Root;
+
+    fn parse(src: &str) -> (SyntaxNode, SemanticModel) {
+        let root = Root::parse(src).syntax();
+        let model = SemanticModel::new(&root);
+        (root, model)
+    }
+
+    // the `n`th identifier named `name`
+    fn ident(root: &SyntaxNode, name: &str, n: usize) -> SyntaxNode {
+        root.descendants()
+            .filter(|node| {
+                matches!(
+                    node.kind(),
+                    SyntaxKind::NODE_IDENT | SyntaxKind::NODE_STRING
+                ) && node.text() == name
+            })
+            .nth(n)
+            .unwrap()
+    }
+
+    #[test]
+    fn let_in() {
+        let (root, model) = parse("let a = 1; b = a; in a + b");
+        let a = model.binding_at(&ident(&root, "a", 0)).unwrap();
+        assert_eq!(a.kind, BindingKind::Attr);
+        assert_eq!(a.references.len(), 2);
+        assert_eq!(model.definition(&ident(&root, "a", 2)).unwrap().id, a.id);
+    }
+
+    #[test]
+    fn rec_attr_set() {
+        let (root, model) = parse("rec { a = 1; b = a; }");
+        assert!(model.definition(&ident(&root, "a", 1)).is_some());
+
+        let (root, model) = parse("{ a = 1; b = a; }");
+        assert_eq!(
+            model.resolve(&ident(&root, "a", 1)),
+            Some(&Resolution::Unresolved)
+        );
+    }
+
+    #[test]
+    fn lambda() {
+        let (root, model) = parse("{ a, b ? a, ... }@args: args");
+        let a = model.definition(&ident(&root, "a", 1)).unwrap();
+        assert_eq!(a.kind, BindingKind::Formal);
+        let args = model.definition(&ident(&root, "args", 1)).unwrap();
+        assert_eq!(args.kind, BindingKind::PatternBind);
+        assert!(
+            model
+                .binding_at(&ident(&root, "b", 0))
+                .unwrap()
+                .references
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn with() {
+        let (root, model) = parse("pkgs: with pkgs; [ hello map pkgs ]");
+        assert!(matches!(
+            model.resolve(&ident(&root, "hello", 0)),
+            Some(Resolution::With(scopes)) if scopes.len() == 1
+        ));
+        let map = model.definition(&ident(&root, "map", 0)).unwrap();
+        assert_eq!(map.kind, BindingKind::Builtin);
+        let pkgs = model.definition(&ident(&root, "pkgs", 2)).unwrap();
+        assert_eq!(pkgs.kind, BindingKind::Param);
+    }
+
+    #[test]
+    fn inherit() {
+        let (root, model) =
+            parse(r#"let a = 1; in let inherit a; inherit ({ b = 2; }) "b"; in a + b"#);
+        let outer = model.binding_at(&ident(&root, "a", 0)).unwrap();
+        let inner = model.binding_at(&ident(&root, "a", 1)).unwrap();
+        assert_eq!(inner.kind, BindingKind::Inherit);
+        assert_eq!(
+            model.definition(&ident(&root, "a", 1)).unwrap().id,
+            outer.id
+        );
+        assert_eq!(
+            model.definition(&ident(&root, "a", 2)).unwrap().id,
+            inner.id
+        );
+        let b = model.definition(&ident(&root, "b", 1)).unwrap();
+        assert_eq!(b.kind, BindingKind::Inherit);
+    }
+
+    #[test]
+    fn shadowing() {
+        let (root, model) = parse("a: let a = 1; in a");
+        let binding = model.definition(&ident(&root, "a", 2)).unwrap();
+        assert_eq!(binding.kind, BindingKind::Attr);
+        assert!(
+            model
+                .binding_at(&ident(&root, "a", 0))
+                .unwrap()
+                .references
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,151 @@
+//! The Nix version that files are linted for, and the options lints are
+//! configured with. Rules that only apply to some versions of Nix, or that
+//! recommend builtins introduced in later versions, read it from the
+//! [`Context`](crate::Context).
+
+use std::{fmt, str::FromStr, sync::Arc};
+
+use crate::{
+    Metadata,
+    options::{FromOptionValue, LintOptions},
+};
+
+/// A Nix version, pre-release suffixes are ignored, so that
+/// `2.4pre20211006_53e4794` is taken as `2.4.0`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version {
+    pub major: u16,
+    pub minor: u16,
+    pub patch: u16,
+}
+
+impl Version {
+    #[must_use]
+    pub const fn new(major: u16, minor: u16, patch: u16) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+}
+
+impl FromStr for Version {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // the leading digits of a component, `4pre2021` is `4`
+        let number = |part: Option<&str>| -> Option<u16> {
+            let part = part?;
+            let end = part
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(part.len());
+            part[..end].parse().ok()
+        };
+        let mut parts = s.trim().split('.');
+        let major = number(parts.next());
+        let minor = number(parts.next());
+        let patch = parts.next().map(|p| number(Some(p)));
+        match (major, minor, patch) {
+            (Some(major), Some(minor), None) => Ok(Self::new(major, minor, 0)),
+            (Some(major), Some(minor), Some(Some(patch))) => Ok(Self::new(major, minor, patch)),
+            _ => Err(s.to_owned()),
+        }
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Settings shared by every file linted in a session
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SessionInfo {
+    nix_version: Option<Version>,
+    options: Arc<LintOptions>,
+}
+
+impl SessionInfo {
+    /// Lint for `nix_version`, or for the latest Nix if it is not known
+    #[must_use]
+    pub fn from_version(nix_version: Option<Version>) -> Self {
+        Self {
+            nix_version,
+            ..Self::default()
+        }
+    }
+
+    /// Lint with `options` in place of the defaults declared by lints
+    #[must_use]
+    pub fn with_options(self, options: LintOptions) -> Self {
+        Self {
+            options: Arc::new(options),
+            ..self
+        }
+    }
+
+    /// The targeted Nix version, if known
+    #[must_use]
+    pub fn version(&self) -> Option<Version> {
+        self.nix_version
+    }
+
+    /// Whether the targeted Nix is `version` or newer. A session without a
+    /// version targets the latest Nix.
+    #[must_use]
+    pub fn at_least(&self, version: Version) -> bool {
+        self.nix_version.is_none_or(|v| v >= version)
+    }
+
+    /// The configured value of the option `name` of `lint`, or its default
+    ///
+    /// # Panics
+    ///
+    /// If `lint` does not declare `name` as an option of type `T`, options
+    /// read from config files are checked against their declarations as
+    /// they are loaded.
+    #[must_use]
+    pub fn option<T: FromOptionValue>(&self, lint: &(impl Metadata + ?Sized), name: &str) -> T {
+        let configured = self
+            .options
+            .get(lint.name())
+            .and_then(|options| options.get(name))
+            .copied();
+        configured
+            .or_else(|| {
+                lint.options()
+                    .iter()
+                    .find(|o| o.name == name)
+                    .map(|o| o.default)
+            })
+            .and_then(T::from_option_value)
+            .unwrap_or_else(|| panic!("`{}` has no option `{name}` of this type", lint.name()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SessionInfo, Version};
+
+    #[test]
+    fn parse_versions() {
+        let parse = |s: &str| s.parse::<Version>();
+        assert_eq!(parse("2.3"), Ok(Version::new(2, 3, 0)));
+        assert_eq!(parse("2.18.1"), Ok(Version::new(2, 18, 1)));
+        assert_eq!(parse("2.4pre20211006_53e4794"), Ok(Version::new(2, 4, 0)));
+        assert_eq!(parse("2.25.0pre20241101"), Ok(Version::new(2, 25, 0)));
+        assert!(parse("2").is_err());
+        assert!(parse("two.three").is_err());
+        assert!(parse("2.3.x").is_err());
+    }
+
+    #[test]
+    fn compare_versions() {
+        assert!(Version::new(2, 10, 0) > Version::new(2, 4, 0));
+        let old = SessionInfo::from_version(Some(Version::new(2, 3, 16)));
+        assert!(!old.at_least(Version::new(2, 4, 0)));
+        assert!(old.at_least(Version::new(2, 0, 0)));
+        assert!(SessionInfo::default().at_least(Version::new(99, 0, 0)));
+    }
+}
@@ -0,0 +1,217 @@
+use crate::{LINTS, Report};
+
+use rnix::{NodeOrToken, SyntaxKind, SyntaxNode, SyntaxToken, TextRange, TextSize};
+
+/// A single lint named inside a suppression comment.
+#[derive(Debug)]
+pub struct Allowed {
+    /// Name of the lint as written in the comment
+    pub name: String,
+    /// Location of the name within the source
+    pub at: TextRange,
+    /// Code of the lint, `None` if `name` is not a known lint
+    pub code: Option<u32>,
+}
+
+/// A parsed `# statix: allow(..)` or `# statix: allow-file(..)` comment.
+#[derive(Debug)]
+struct Suppression {
+    /// Region of source covered by this suppression, `None` if it
+    /// covers the entire file
+    scope: Option<TextRange>,
+    allowed: Vec<Allowed>,
+}
+
+impl Suppression {
+    fn covers(&self, report: &Report) -> bool {
+        if !self.allowed.iter().any(|a| a.code == Some(report.code)) {
+            return false;
+        }
+        match (self.scope, report.total_diagnostic_range()) {
+            (None, _) => true,
+            (Some(scope), Some(at)) => scope.contains_inclusive(at.start()),
+            (Some(_), None) => false,
+        }
+    }
+}
+
+/// Result of parsing the text of a single comment.
+pub(crate) enum Directive<'a> {
+    /// `allow(..)`, applies to the next line or the following binding
+    Allow(Vec<(&'a str, TextRange)>),
+    /// `allow-file(..)`, applies to the whole file
+    AllowFile(Vec<(&'a str, TextRange)>),
+    /// The comment mentions `statix:` but is not a valid directive
+    Malformed,
+}
+
+/// Parse a comment token into a suppression directive. Returns `None` if
+/// the comment is an ordinary comment. Ranges are relative to the start of
+/// the comment text.
+pub(crate) fn parse_directive(comment: &str) -> Option<Directive<'_>> {
+    let body = if let Some(rest) = comment.strip_prefix('#') {
+        rest
+    } else {
+        comment.strip_prefix("/*")?.strip_suffix("*/")?
+    };
+    let body = body.trim_start().strip_prefix("statix:")?.trim();
+
+    let (args, is_file) = if let Some(rest) = body.strip_prefix("allow-file") {
+        (rest, true)
+    } else if let Some(rest) = body.strip_prefix("allow") {
+        (rest, false)
+    } else {
+        return Some(Directive::Malformed);
+    };
+
+    let Some(args) = args
+        .trim_start()
+        .strip_prefix('(')
+        .and_then(|a| a.strip_suffix(')'))
+    else {
+        return Some(Directive::Malformed);
+    };
+
+    let offset_of = |s: &str| s.as_ptr() as usize - comment.as_ptr() as usize;
+    let names = args
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            let start = TextSize::try_from(offset_of(name)).unwrap();
+            (name, TextRange::at(start, TextSize::of(name)))
+        })
+        .collect::<Vec<_>>();
+
+    if names.is_empty() {
+        return Some(Directive::Malformed);
+    }
+
+    Some(if is_file {
+        Directive::AllowFile(names)
+    } else {
+        Directive::Allow(names)
+    })
+}
+
+/// Suppression comments present in a file.
+///
+/// A comment of the form `# statix: allow(lint_a, lint_b)` suppresses the
+/// named lints on the line that follows it, or within the binding or
+/// expression that immediately follows it. When placed at the end of a line
+/// of code, it suppresses lints on that line instead. A comment of the form
+/// `# statix: allow-file(lint_a)` suppresses the named lints in the entire
+/// file.
+#[derive(Debug, Default)]
+pub struct Suppressions {
+    suppressions: Vec<Suppression>,
+}
+
+impl Suppressions {
+    /// Collect all suppression comments under `root`
+    #[must_use]
+    pub fn new(root: &SyntaxNode) -> Self {
+        let src = root.text().to_string();
+        let suppressions = root
+            .descendants_with_tokens()
+            .filter_map(NodeOrToken::into_token)
+            .filter(|token| token.kind() == SyntaxKind::TOKEN_COMMENT)
+            .filter_map(|token| {
+                let (names, is_file) = match parse_directive(token.text())? {
+                    Directive::Allow(names) => (names, false),
+                    Directive::AllowFile(names) => (names, true),
+                    Directive::Malformed => return None,
+                };
+                let start = token.text_range().start();
+                let allowed = names
+                    .into_iter()
+                    .map(|(name, at)| Allowed {
+                        name: name.to_owned(),
+                        at: at + start,
+                        code: LINTS.iter().find(|l| l.name() == name).map(|l| l.code()),
+                    })
+                    .collect();
+                let scope = if is_file {
+                    None
+                } else {
+                    Some(scope_of(&token, &src))
+                };
+                Some(Suppression { scope, allowed })
+            })
+            .collect();
+        Self { suppressions }
+    }
+
+    /// Check if `report` is suppressed by any comment
+    #[must_use]
+    pub fn suppresses(&self, report: &Report) -> bool {
+        self.suppressions.iter().any(|s| s.covers(report))
+    }
+
+    /// Split `reports` into reports that are kept and reports that are
+    /// suppressed
+    #[must_use]
+    pub fn partition(&self, reports: Vec<Report>) -> (Vec<Report>, Vec<Report>) {
+        reports.into_iter().partition(|r| !self.suppresses(r))
+    }
+
+    /// Lints named in suppression comments that did not suppress any of
+    /// `suppressed`. Unknown lint names are not included.
+    pub fn unused<'a>(&'a self, suppressed: &'a [Report]) -> impl Iterator<Item = &'a Allowed> {
+        self.suppressions.iter().flat_map(move |s| {
+            s.allowed.iter().filter(move |allowed| {
+                allowed.code.is_some()
+                    && !suppressed
+                        .iter()
+                        .any(|r| Some(r.code) == allowed.code && s.covers(r))
+            })
+        })
+    }
+}
+
+/// The region covered by an `allow` comment: the line that follows the
+/// comment along with the next sibling element, or the current line if the
+/// comment trails code.
+fn scope_of(comment: &SyntaxToken, src: &str) -> TextRange {
+    let comment_range = comment.text_range();
+    let comment_start = usize::from(comment_range.start());
+    let comment_end = usize::from(comment_range.end());
+
+    let line_start = src[..comment_start].rfind('\n').map_or(0, |i| i + 1);
+    let is_trailing = !src[line_start..comment_start].trim().is_empty();
+
+    if is_trailing {
+        let line_end = src[comment_end..]
+            .find('\n')
+            .map_or(src.len(), |i| comment_end + i);
+        return TextRange::new(
+            TextSize::try_from(line_start).unwrap(),
+            TextSize::try_from(line_end).unwrap(),
+        );
+    }
+
+    let next_line_end = {
+        let next_line_start = src[comment_end..]
+            .find('\n')
+            .map_or(src.len(), |i| comment_end + i + 1);
+        src[next_line_start..]
+            .find('\n')
+            .map_or(src.len(), |i| next_line_start + i)
+    };
+
+    let next_sibling_end = std::iter::successors(comment.next_sibling_or_token(), |el| {
+        el.next_sibling_or_token()
+    })
+    .find(|el| {
+        !matches!(
+            el.kind(),
+            SyntaxKind::TOKEN_WHITESPACE | SyntaxKind::TOKEN_COMMENT
+        )
+    })
+    .map_or(0, |el| usize::from(el.text_range().end()));
+
+    TextRange::new(
+        comment_range.end(),
+        TextSize::try_from(next_line_end.max(next_sibling_end)).unwrap(),
+    )
+}
@@ -42,6 +42,15 @@ pub struct LintMeta<'μ> {
     note: &'μ Lit,
     code: &'μ Lit,
     match_with: MatchWith<'μ>,
+    options: Vec<LintOption<'μ>>,
+}
+
+// `name = default`, from `options = [threshold = 3, ...]`, integers may
+// declare a minimum as in `threshold(min = 2) = 3`
+struct LintOption<'ο> {
+    name: &'ο Ident,
+    default: &'ο Lit,
+    min: Option<&'ο Lit>,
 }
 
 enum MatchWith<'π> {
@@ -55,6 +64,40 @@ fn extract<'λ>(id: &str, raw: &'λ RawLintMeta) -> &'λ Expr {
         .unwrap_or_else(|| panic!("`{id}` not present"))
 }
 
+fn as_option(e: &Expr) -> LintOption<'_> {
+    let Expr::Assign(assign) = e else {
+        panic!("expected an option of the form `name = default`");
+    };
+    let (name, min) = match &*assign.left {
+        Expr::Call(call) => {
+            let [Expr::Assign(min)] = call.args.iter().collect::<Vec<_>>()[..] else {
+                panic!("expected a minimum of the form `min = value`");
+            };
+            assert!(
+                as_ident(&min.left) == "min",
+                "expected a minimum of the form `min = value`"
+            );
+            (as_ident(&call.func), Some(as_lit(&min.right)))
+        }
+        name => (as_ident(name), None),
+    };
+    LintOption {
+        name,
+        default: as_lit(&assign.right),
+        min,
+    }
+}
+
+fn as_ident(e: &Expr) -> &Ident {
+    match e {
+        Expr::Path(p) => p
+            .path
+            .get_ident()
+            .unwrap_or_else(|| panic!("expected an identifier")),
+        _ => panic!("expected an identifier"),
+    }
+}
+
 fn as_lit(e: &Expr) -> &Lit {
     match e {
         Expr::Lit(l) => &l.lit,
@@ -73,11 +116,17 @@ impl<'μ> LintMeta<'μ> {
             Expr::Array(a) => MatchWith::Array(a),
             _ => panic!("`match_with` is neither a path nor an array"),
         };
+        let options = match raw.0.get(&format_ident!("options")) {
+            Some(Expr::Array(a)) => a.elems.iter().map(as_option).collect(),
+            Some(_) => panic!("`options` is not an array"),
+            None => Vec::new(),
+        };
         Self {
             name,
             note,
             code,
             match_with,
+            options,
         }
     }
 
@@ -146,6 +195,37 @@ impl<'μ> LintMeta<'μ> {
         }
     }
 
+    fn generate_options_fn(&self) -> TokenStream2 {
+        let options = self
+            .options
+            .iter()
+            .map(|LintOption { name, default, min }| {
+                let name = name.to_string();
+                let default = match default {
+                    Lit::Bool(_) => quote! { crate::options::OptionValue::Bool(#default) },
+                    Lit::Int(_) => quote! { crate::options::OptionValue::Int(#default) },
+                    _ => panic!("option `{name}` is neither a boolean nor an integer"),
+                };
+                let min = match min {
+                    Some(min @ Lit::Int(_)) => quote! { Some(#min) },
+                    Some(_) => panic!("the minimum of option `{name}` is not an integer"),
+                    None => quote! { None },
+                };
+                quote! {
+                    crate::options::LintOption {
+                        name: #name,
+                        default: #default,
+                        min: #min,
+                    }
+                }
+            });
+        quote! {
+            fn options(&self) -> &'static [crate::options::LintOption] {
+                &[#(#options),*]
+            }
+        }
+    }
+
     fn generate_report_fn() -> TokenStream2 {
         quote! {
             fn report(&self) -> crate::Report {
@@ -162,6 +242,7 @@ pub fn generate_meta_impl(struct_name: &Ident, meta: &RawLintMeta) -> TokenStrea
     let code_fn = not_raw.generate_code_fn();
     let match_with_fn = not_raw.generate_match_with_fn();
     let match_kind = not_raw.generate_match_kind_fn();
+    let options_fn = not_raw.generate_options_fn();
     let report_fn = LintMeta::generate_report_fn();
 
     quote! {
@@ -171,6 +252,7 @@ pub fn generate_meta_impl(struct_name: &Ident, meta: &RawLintMeta) -> TokenStrea
             #code_fn
             #match_with_fn
             #match_kind
+            #options_fn
             #report_fn
         }
     }
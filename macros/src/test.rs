@@ -2,7 +2,7 @@ use proc_macro::TokenStream;
 use quote::{ToTokens, quote};
 use sha2::{Digest, Sha256};
 use syn::{
-    Error, Expr, ExprArray, Ident, Token,
+    Error, Expr, ExprArray, Ident, LitBool, Token,
     parse::{Parse, ParseStream},
     parse_macro_input,
     punctuated::Punctuated,
@@ -13,12 +13,16 @@ use syn::{
 struct MacroInvocation {
     rule: Ident,
     expressions: Punctuated<Expr, Comma>,
+    config: Option<Expr>,
+    unsafe_fixes: bool,
 }
 
 impl Parse for MacroInvocation {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         const RULE_VALUE: &str = "rule";
         const EXPRESSSIONS_VALUE: &str = "expressions";
+        const CONFIG_VALUE: &str = "config";
+        const UNSAFE_FIXES_VALUE: &str = "unsafe_fixes";
         let rule_attribute = input.parse::<Ident>()?;
 
         if rule_attribute != RULE_VALUE {
@@ -46,22 +50,58 @@ impl Parse for MacroInvocation {
         } = input.parse::<ExprArray>()?;
 
         input.parse::<Token![,]>()?;
-        Ok(MacroInvocation { rule, expressions })
+
+        // the contents of a `statix.toml` the expressions are checked with,
+        // and whether fixes that are not applied by default are snapshotted
+        // too
+        let mut config = None;
+        let mut unsafe_fixes = false;
+        while !input.is_empty() {
+            let attribute = input.parse::<Ident>()?;
+            input.parse::<Token![:]>()?;
+            if attribute == CONFIG_VALUE {
+                config = Some(input.parse::<Expr>()?);
+            } else if attribute == UNSAFE_FIXES_VALUE {
+                unsafe_fixes = input.parse::<LitBool>()?.value;
+            } else {
+                return Err(Error::new(
+                    attribute.span(),
+                    "expected `{CONFIG_VALUE:?}` or `{UNSAFE_FIXES_VALUE:?}`",
+                ));
+            }
+            input.parse::<Token![,]>()?;
+        }
+
+        Ok(MacroInvocation {
+            rule,
+            expressions,
+            config,
+            unsafe_fixes,
+        })
     }
 }
 
 pub fn generate_tests(input: TokenStream) -> TokenStream {
-    let MacroInvocation { rule, expressions } = parse_macro_input!(input as MacroInvocation);
+    let MacroInvocation {
+        rule,
+        expressions,
+        config,
+        unsafe_fixes,
+    } = parse_macro_input!(input as MacroInvocation);
     expressions
         .into_iter()
         .map(|nix_expression| {
-            let lint_test = make_test(&rule, TestKind::Lint, &nix_expression);
-            let fix_test = make_test(&rule, TestKind::Fix, &nix_expression);
+            let lint_test = make_test(&rule, TestKind::Lint, &nix_expression, config.as_ref());
+            let fix_test = make_test(&rule, TestKind::Fix, &nix_expression, config.as_ref());
+            let unsafe_fix_test = unsafe_fixes
+                .then(|| make_test(&rule, TestKind::UnsafeFix, &nix_expression, config.as_ref()));
 
             quote! {
                 #lint_test
 
                 #fix_test
+
+                #unsafe_fix_test
             }
         })
         .collect::<proc_macro2::TokenStream>()
@@ -72,15 +112,22 @@ pub fn generate_tests(input: TokenStream) -> TokenStream {
 enum TestKind {
     Lint,
     Fix,
+    UnsafeFix,
 }
 
-fn make_test(rule: &Ident, kind: TestKind, nix_expression: &Expr) -> proc_macro2::TokenStream {
+fn make_test(
+    rule: &Ident,
+    kind: TestKind,
+    nix_expression: &Expr,
+    config: Option<&Expr>,
+) -> proc_macro2::TokenStream {
     let expression_hash = Sha256::digest(nix_expression.to_token_stream().to_string());
     let expression_hash = hex::encode(expression_hash);
 
     let kind_str = match kind {
         TestKind::Lint => "lint",
         TestKind::Fix => "fix",
+        TestKind::UnsafeFix => "unsafe_fix",
     };
 
     let test_name = format!("{rule}_{kind_str}_{expression_hash}");
@@ -90,13 +137,20 @@ fn make_test(rule: &Ident, kind: TestKind, nix_expression: &Expr) -> proc_macro2
     let args = match kind {
         TestKind::Lint => quote! {&["check"]},
         TestKind::Fix => quote! {&["fix", "--dry-run"]},
+        TestKind::UnsafeFix => quote! {&["fix", "--dry-run", "--unsafe-fixes"]},
+    };
+
+    let run = if let Some(config) = config {
+        quote! {_utils::test_cli_with_config(expression, #config, #args)}
+    } else {
+        quote! {_utils::test_cli(expression, #args)}
     };
 
     quote! {
         #[test]
         fn #test_ident() {
             let expression = #nix_expression;
-            let stdout = _utils::test_cli(expression, #args).unwrap();
+            let stdout = #run.unwrap();
             insta::assert_snapshot!(#snap_name, stdout, &format!("{expression:?}"));
         }
     }
@@ -28,6 +28,8 @@ impl Interner {
     }
 }
 
+/// Files are iterated over in the order of their paths, so that output does
+/// not change from one run to the next
 #[derive(Default)]
 pub struct ReadOnlyVfs {
     interner: Interner,
@@ -51,6 +53,9 @@ impl ReadOnlyVfs {
     pub fn is_empty(&self) -> bool {
         self.data.is_empty()
     }
+    pub fn file_id<P: AsRef<Path>>(&self, path: P) -> Option<FileId> {
+        self.interner.get(path)
+    }
     #[must_use]
     pub fn file_path(&self, file_id: FileId) -> &Path {
         self.interner.lookup(file_id).unwrap()
@@ -70,20 +75,35 @@ impl ReadOnlyVfs {
         let file_id = self.alloc_file_id(path);
         self.data.insert(file_id, contents.to_owned());
     }
+    /// Drop the contents of a file, its `FileId` remains valid and is reused
+    /// if the file is added again
+    pub fn remove_file(&mut self, file_id: FileId) {
+        self.data.remove(&file_id);
+    }
+    #[must_use]
+    pub fn entry(&self, file_id: FileId) -> VfsEntry<'_> {
+        VfsEntry {
+            file_id,
+            file_path: self.file_path(file_id),
+            contents: self.get_str(file_id),
+        }
+    }
     pub fn iter(&self) -> impl Iterator<Item = VfsEntry<'_>> {
-        self.data.keys().map(move |file_id| VfsEntry {
-            file_id: *file_id,
-            file_path: self.file_path(*file_id),
-            contents: self.get_str(*file_id),
-        })
+        self.sorted_ids()
+            .into_iter()
+            .map(move |file_id| self.entry(file_id))
     }
+    /// Entries are processed in parallel, but collected in order
     #[must_use]
-    pub fn par_iter(&self) -> impl ParallelIterator<Item = VfsEntry<'_>> {
-        self.data.par_iter().map(move |(file_id, _)| VfsEntry {
-            file_id: *file_id,
-            file_path: self.file_path(*file_id),
-            contents: self.get_str(*file_id),
-        })
+    pub fn par_iter(&self) -> impl IndexedParallelIterator<Item = VfsEntry<'_>> {
+        self.sorted_ids()
+            .into_par_iter()
+            .map(move |file_id| self.entry(file_id))
+    }
+    fn sorted_ids(&self) -> Vec<FileId> {
+        let mut ids = self.data.keys().copied().collect::<Vec<_>>();
+        ids.sort_by(|a, b| self.file_path(*a).cmp(self.file_path(*b)));
+        ids
     }
 }
 
@@ -106,4 +126,28 @@ mod test {
         vfs.set_file_contents(f1, &data);
         assert_eq!(vfs.get(id1), &data);
     }
+
+    #[test]
+    fn sorted_by_path() {
+        let mut vfs = ReadOnlyVfs::default();
+        for path in ["b/a", "a/b", "c", "a/a"] {
+            vfs.set_file_contents(path, b"");
+        }
+        let paths = vfs.iter().map(|e| e.file_path).collect::<Vec<_>>();
+        assert_eq!(paths, ["a/a", "a/b", "b/a", "c"].map(Path::new));
+        let paths = vfs.par_iter().map(|e| e.file_path).collect::<Vec<_>>();
+        assert_eq!(paths, ["a/a", "a/b", "b/a", "c"].map(Path::new));
+    }
+
+    #[test]
+    fn remove() {
+        let mut vfs = ReadOnlyVfs::default();
+        vfs.set_file_contents("a", b"hello");
+        let id = vfs.file_id("a").unwrap();
+        vfs.remove_file(id);
+        assert!(vfs.is_empty());
+        vfs.set_file_contents("a", b"world");
+        assert_eq!(vfs.file_id("a"), Some(id));
+        assert_eq!(vfs.get_str(id), "world");
+    }
 }